@@ -0,0 +1,6 @@
+// Rebuild when a migration is added, sqlx::migrate! only tracks files it has already seen,
+// and when the offline query metadata changes, which the query macros don't track at all
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+    println!("cargo:rerun-if-changed=.sqlx");
+}
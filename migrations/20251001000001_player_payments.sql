@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS `tb_player_payments` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `sender_uuid` varchar(36) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `recipient_uuid` varchar(36) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `source` enum('WALLET','BANK') COLLATE utf8mb4_unicode_ci NOT NULL,
+  `amount` bigint NOT NULL,
+  `fee` bigint NOT NULL,
+  `timestamp` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_sender_uuid` (`sender_uuid`),
+  KEY `idx_recipient_uuid` (`recipient_uuid`),
+  KEY `idx_timestamp` (`timestamp`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('p2p_fee_rate', 0.0500, 'Player to player payment fee, paid by the sender (5%)');
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS `tb_ledger_journal` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `reason` varchar(32) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `reference` varchar(64) COLLATE utf8mb4_unicode_ci DEFAULT NULL,
+  `created_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_reason` (`reason`),
+  KEY `idx_created_at` (`created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS `tb_ledger` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `journal_id` bigint NOT NULL,
+  `account_type` enum('WALLET','BANK','SYSTEM') COLLATE utf8mb4_unicode_ci NOT NULL,
+  `account_id` varchar(36) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'Player UUID, or the system account name',
+  `amount` bigint NOT NULL COMMENT 'Signed: positive increases the account balance',
+  `created_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_journal_id` (`journal_id`),
+  KEY `idx_account` (`account_id`,`account_type`),
+  CONSTRAINT `tb_ledger_ibfk_1` FOREIGN KEY (`journal_id`) REFERENCES `tb_ledger_journal` (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS `tb_api_keys` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `name` varchar(100) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `key_hash` char(64) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'SHA-256 of the bearer key',
+  `hmac_secret` varchar(128) COLLATE utf8mb4_unicode_ci DEFAULT NULL COMMENT 'Shared secret for signed requests',
+  `scope` enum('READ','PLAYER_OPS','ADMIN') COLLATE utf8mb4_unicode_ci NOT NULL DEFAULT 'READ',
+  `is_active` tinyint(1) NOT NULL DEFAULT '1',
+  `created_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  `last_used_at` timestamp NULL DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `key_hash` (`key_hash`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
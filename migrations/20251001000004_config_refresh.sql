@@ -0,0 +1,2 @@
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('config_refresh_interval_secs', 60.0000, 'How often the server reloads tb_config (seconds)');
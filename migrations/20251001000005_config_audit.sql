@@ -0,0 +1,13 @@
+ALTER TABLE `tb_config`
+  ADD COLUMN `updated_by` varchar(100) COLLATE utf8mb4_unicode_ci DEFAULT NULL AFTER `description`;
+
+CREATE TABLE IF NOT EXISTS `tb_config_audit` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `config_key` varchar(100) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `old_value` decimal(10,4) NOT NULL,
+  `new_value` decimal(10,4) NOT NULL,
+  `changed_by` varchar(100) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `changed_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_config_key` (`config_key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,4 @@
+ALTER TABLE `tb_market_items`
+  ADD COLUMN `min_multiplier` double NOT NULL DEFAULT '0.1' AFTER `price_multiplier`,
+  ADD COLUMN `max_multiplier` double NOT NULL DEFAULT '4' AFTER `min_multiplier`,
+  ADD COLUMN `is_enabled` tinyint(1) NOT NULL DEFAULT '1' AFTER `max_multiplier`;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS `tb_bank_sessions` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `player_uuid` varchar(36) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `opened_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  `expires_at` timestamp NOT NULL,
+  `closed_at` timestamp NULL DEFAULT NULL,
+  `close_reason` varchar(32) COLLATE utf8mb4_unicode_ci DEFAULT NULL,
+  PRIMARY KEY (`id`),
+  KEY `idx_player_open` (`player_uuid`,`closed_at`),
+  KEY `idx_expires_at` (`expires_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('bank_session_timeout_secs', 300.0000, 'Bank sessions close automatically after this many seconds');
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS `tb_price_history` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `item_key` varchar(255) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `price_multiplier` double NOT NULL,
+  `sell_price` bigint NOT NULL,
+  `buy_price` bigint NOT NULL,
+  `source` enum('TRADE','REGENERATION','ADMIN') COLLATE utf8mb4_unicode_ci NOT NULL,
+  `recorded_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_item_time` (`item_key`,`recorded_at`),
+  CONSTRAINT `tb_price_history_ibfk_1` FOREIGN KEY (`item_key`) REFERENCES `tb_market_items` (`item_key`) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS `tb_pricing_models` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `scope` varchar(255) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'item_key, or * for the global default',
+  `config` text COLLATE utf8mb4_unicode_ci NOT NULL,
+  `updated_by` varchar(100) COLLATE utf8mb4_unicode_ci DEFAULT NULL,
+  `updated_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `scope` (`scope`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
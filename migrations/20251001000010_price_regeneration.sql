@@ -0,0 +1,23 @@
+ALTER TABLE `tb_market_items`
+  ADD COLUMN `regeneration_decay` double DEFAULT NULL AFTER `max_multiplier`;
+
+CREATE TABLE IF NOT EXISTS `tb_regeneration_runs` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `trigger_type` enum('SCHEDULED','MANUAL') COLLATE utf8mb4_unicode_ci NOT NULL,
+  `triggered_by` varchar(100) COLLATE utf8mb4_unicode_ci DEFAULT NULL,
+  `status` enum('SUCCESS','FAILED') COLLATE utf8mb4_unicode_ci NOT NULL,
+  `items_updated` int NOT NULL DEFAULT '0',
+  `decay_rate` double NOT NULL,
+  `error` text COLLATE utf8mb4_unicode_ci,
+  `started_at` timestamp NOT NULL,
+  `finished_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_status` (`status`,`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('regeneration_interval_secs', 10800.0000, 'How often market prices drift back toward base (seconds)'),
+('regeneration_decay_rate', 0.1000, 'Share of the distance to a 1.0 multiplier recovered per regeneration (10%)'),
+('regeneration_max_multiplier', 4.0000, 'Highest multiplier an item is left at after regeneration'),
+('regeneration_volume_reference', 640.0000, 'Units traded since the last regeneration that halve the pull (0 disables)'),
+('regeneration_paused', 0.0000, 'Set to 1 to pause scheduled price regeneration');
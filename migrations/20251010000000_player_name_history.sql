@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS `tb_player_name_history` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `player_uuid` varchar(36) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `old_name` varchar(16) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `new_name` varchar(16) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `changed_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  KEY `idx_player_uuid` (`player_uuid`),
+  KEY `idx_old_name` (`old_name`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
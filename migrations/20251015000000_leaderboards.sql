@@ -0,0 +1,5 @@
+ALTER TABLE `tb_user`
+  ADD COLUMN `exclude_from_leaderboards` tinyint(1) NOT NULL DEFAULT '0' AFTER `is_bank_open`;
+
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('leaderboard_cache_secs', 60.0000, 'How long leaderboards are cached before being recomputed (seconds, 0 disables)');
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS `tb_fee_schedules` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `operation` varchar(32) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'wallet_to_bank, bank_to_wallet, market_sell, market_buy or p2p',
+  `config` text COLLATE utf8mb4_unicode_ci NOT NULL,
+  `updated_by` varchar(100) COLLATE utf8mb4_unicode_ci DEFAULT NULL,
+  `updated_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `operation` (`operation`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
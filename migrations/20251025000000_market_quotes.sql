@@ -0,0 +1,2 @@
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('market_quote_ttl_secs', 30.0000, 'How long a market quote can be used to sell or buy (seconds)');
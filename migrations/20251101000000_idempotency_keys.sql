@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS `tb_idempotency_keys` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `client_id` int NOT NULL COMMENT 'tb_api_keys.id of the caller, keys are scoped per client',
+  `idempotency_key` varchar(255) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `request_hash` char(64) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'SHA-256 of method, path and body',
+  `status_code` smallint unsigned DEFAULT NULL COMMENT 'NULL while the first request is still running',
+  `response_body` mediumblob,
+  `created_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
+  `expires_at` timestamp NOT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `client_key` (`client_id`,`idempotency_key`),
+  KEY `idx_expires_at` (`expires_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO `tb_config` (`config_key`, `config_value`, `description`) VALUES
+('idempotency_key_ttl_secs', 86400.0000, 'How long an Idempotency-Key and its stored response are kept (seconds)');
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS `tb_used_signatures` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `client_id` int NOT NULL COMMENT 'tb_api_keys.id that signed the request',
+  `signature` char(64) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT 'Lowercase hex HMAC of a state-changing request',
+  `expires_at` timestamp NOT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `client_signature` (`client_id`,`signature`),
+  KEY `idx_expires_at` (`expires_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,2 @@
+ALTER TABLE `tb_idempotency_keys`
+  ADD COLUMN `locked_until` timestamp NULL DEFAULT NULL COMMENT 'Lease on an unfinished claim, renewed while its request runs' AFTER `response_body`;
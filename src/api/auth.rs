@@ -0,0 +1,394 @@
+// api/auth.rs
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::{Json, Response},
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::{api::error::ApiError, AppState};
+
+/// Signed requests older or newer than this are rejected to limit replays.
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+/// Largest body we'll buffer to verify an HMAC signature.
+const MAX_SIGNED_BODY_BYTES: usize = 1024 * 1024;
+/// `last_used_at` is written at most this often per key.
+const LAST_USED_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+
+const KEY_ID_HEADER: &str = "x-moji-key-id";
+const TIMESTAMP_HEADER: &str = "x-moji-timestamp";
+const SIGNATURE_HEADER: &str = "x-moji-signature";
+
+/// What an API client is allowed to do. Each scope includes the ones below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    Read,
+    PlayerOps,
+    Admin,
+}
+
+impl Scope {
+    fn as_db(&self) -> &'static str {
+        match self {
+            Scope::Read => "READ",
+            Scope::PlayerOps => "PLAYER_OPS",
+            Scope::Admin => "ADMIN",
+        }
+    }
+
+    fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "READ" => Some(Scope::Read),
+            "PLAYER_OPS" => Some(Scope::PlayerOps),
+            "ADMIN" => Some(Scope::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// The authenticated caller, available to handlers as an `Extension`.
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    pub id: i32,
+    pub name: String,
+    pub scope: Scope,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scope: Scope,
+    #[serde(default)]
+    pub hmac: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    pub id: u64,
+    pub name: String,
+    pub scope: Scope,
+    /// Only set for bearer keys, an HMAC key must sign every request.
+    pub api_key: Option<String>,
+    pub hmac_secret: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyInfo {
+    pub id: i32,
+    pub name: String,
+    pub scope: String,
+    pub is_active: i8,
+    pub uses_hmac: i64,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// When each key's `last_used_at` was last written, so a busy key costs one
+/// UPDATE a minute rather than one per request.
+#[derive(Clone, Default)]
+pub struct KeyUsage {
+    written: Arc<Mutex<HashMap<i32, Instant>>>,
+}
+
+impl KeyUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True when `last_used_at` is due for a write, which the caller then owes.
+    fn claim(&self, key_id: i32) -> bool {
+        let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if written.get(&key_id).is_some_and(|at| now.duration_since(*at) < LAST_USED_WRITE_INTERVAL) {
+            return false;
+        }
+        written.insert(key_id, now);
+        true
+    }
+}
+
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn generate_secret(prefix: &str) -> String {
+    format!("{}_{}{}", prefix, Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Looks up a bearer key. HMAC keys never match, so a request can't skip
+/// their signature and replay checks by sending the key as a bearer token.
+async fn find_by_key(pool: &MySqlPool, key: &str) -> Result<Option<ApiClient>, sqlx::Error> {
+    let record = sqlx::query!(
+        "SELECT id, name, scope FROM tb_api_keys WHERE key_hash = ? AND is_active = 1 AND hmac_secret IS NULL",
+        hash_key(key)
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record.and_then(|r| {
+        Scope::from_db(&r.scope).map(|scope| ApiClient { id: r.id, name: r.name, scope })
+    }))
+}
+
+async fn find_hmac_client(pool: &MySqlPool, key_id: i32) -> Result<Option<(ApiClient, String)>, sqlx::Error> {
+    let record = sqlx::query!(
+        "SELECT id, name, scope, hmac_secret FROM tb_api_keys WHERE id = ? AND is_active = 1",
+        key_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record.and_then(|r| {
+        let scope = Scope::from_db(&r.scope)?;
+        let secret = r.hmac_secret?;
+        Some((ApiClient { id: r.id, name: r.name, scope }, secret))
+    }))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Verifies `hex(HMAC-SHA256(secret, "{timestamp}\n{METHOD}\n{path?query}\n{body}"))`.
+fn verify_signature(secret: &str, timestamp: &str, method: &str, path: &str, body: &Bytes, signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(timestamp.as_bytes());
+    mac.update(b"\n");
+    mac.update(method.as_bytes());
+    mac.update(b"\n");
+    mac.update(path.as_bytes());
+    mac.update(b"\n");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Remembers a signature until its timestamp has left the allowed window.
+/// Returns false when it was already used, i.e. the request is a replay.
+async fn record_signature(pool: &MySqlPool, client_id: i32, signature: &str) -> Result<bool, sqlx::Error> {
+    // Lowercased, so the same signature can't be replayed in different hex case
+    let result = sqlx::query!(
+        "INSERT IGNORE INTO tb_used_signatures (client_id, signature, expires_at)
+         VALUES (?, ?, DATE_ADD(NOW(), INTERVAL ? SECOND))",
+        client_id,
+        signature.to_ascii_lowercase(),
+        2 * MAX_CLOCK_SKEW_SECS
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Drops signatures whose timestamps can no longer pass the clock skew check,
+/// returning how many were removed.
+pub async fn purge_expired_signatures(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM tb_used_signatures WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn authenticate_hmac(pool: &MySqlPool, request: Request) -> Result<(ApiClient, Request), ApiError> {
+    let headers = request.headers();
+    let key_id = header(headers, KEY_ID_HEADER)
+        .and_then(|v| v.parse::<i32>().ok())
+        .ok_or_else(|| ApiError::Unauthorized("Invalid key id".to_string()))?;
+    let timestamp = header(headers, TIMESTAMP_HEADER)
+        .ok_or_else(|| ApiError::Unauthorized("Missing request timestamp".to_string()))?
+        .to_string();
+    let signature = header(headers, SIGNATURE_HEADER)
+        .ok_or_else(|| ApiError::Unauthorized("Missing request signature".to_string()))?
+        .to_string();
+
+    let sent_at = timestamp
+        .parse::<i64>()
+        .map_err(|_| ApiError::Unauthorized("Invalid request timestamp".to_string()))?;
+    if (chrono::Utc::now().timestamp() - sent_at).abs() > MAX_CLOCK_SKEW_SECS {
+        return Err(ApiError::Unauthorized("Request timestamp outside the allowed window".to_string()));
+    }
+
+    let (client, secret) = find_hmac_client(pool, key_id)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Unknown or revoked key".to_string()))?;
+
+    // The body has to be buffered to sign it, then handed back to the handler
+    let (parts, body) = request.into_parts();
+    let body = axum::body::to_bytes(body, MAX_SIGNED_BODY_BYTES)
+        .await
+        .map_err(|_| ApiError::validation("Request body too large to verify"))?;
+    let path = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+
+    if !verify_signature(&secret, &timestamp, parts.method.as_str(), path, &body, &signature) {
+        return Err(ApiError::Unauthorized("Invalid request signature".to_string()));
+    }
+    // Reads may be resent as they are, anything else has to be signed afresh
+    if !parts.method.is_safe() && !record_signature(pool, client.id, &signature).await? {
+        return Err(ApiError::Unauthorized("Request signature was already used".to_string()));
+    }
+
+    Ok((client, Request::from_parts(parts, Body::from(body))))
+}
+
+/// Resolves the caller from either `Authorization: Bearer <key>` or the
+/// `X-Moji-*` HMAC headers and stores it in the request extensions.
+pub async fn authenticate(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let bearer = header(request.headers(), "authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string);
+
+    let (client, mut request) = if let Some(key) = bearer {
+        let client = find_by_key(&state.pool, &key)
+            .await?
+            .ok_or_else(|| ApiError::Unauthorized("Invalid API key".to_string()))?;
+        (client, request)
+    } else if request.headers().contains_key(SIGNATURE_HEADER) {
+        authenticate_hmac(&state.pool, request).await?
+    } else {
+        return Err(ApiError::Unauthorized("Missing credentials".to_string()));
+    };
+
+    if state.key_usage.claim(client.id) {
+        let pool = state.pool.clone();
+        let client_id = client.id;
+        tokio::spawn(async move {
+            let _ = sqlx::query!("UPDATE tb_api_keys SET last_used_at = NOW() WHERE id = ?", client_id)
+                .execute(&pool)
+                .await;
+        });
+    }
+
+    request.extensions_mut().insert(client);
+    Ok(next.run(request).await)
+}
+
+fn check_scope(request: &Request, required: Scope) -> Result<(), ApiError> {
+    match request.extensions().get::<ApiClient>() {
+        Some(client) if client.scope >= required => Ok(()),
+        Some(client) => {
+            tracing::warn!("🔒 {} ({:?}) denied access to {}", client.name, client.scope, request.uri().path());
+            Err(ApiError::Forbidden(format!("This key lacks the {:?} scope", required)))
+        }
+        None => Err(ApiError::Unauthorized("Missing credentials".to_string())),
+    }
+}
+
+pub async fn require_read(request: Request, next: Next) -> Result<Response, ApiError> {
+    check_scope(&request, Scope::Read)?;
+    Ok(next.run(request).await)
+}
+
+pub async fn require_player_ops(request: Request, next: Next) -> Result<Response, ApiError> {
+    check_scope(&request, Scope::PlayerOps)?;
+    Ok(next.run(request).await)
+}
+
+pub async fn require_admin(request: Request, next: Next) -> Result<Response, ApiError> {
+    check_scope(&request, Scope::Admin)?;
+    Ok(next.run(request).await)
+}
+
+/// Seeds an admin key from `MOJI_ADMIN_KEY` so a fresh install can create
+/// the rest of its keys through the API.
+pub async fn bootstrap_admin_key(pool: &MySqlPool) -> Result<bool, sqlx::Error> {
+    let Ok(key) = std::env::var("MOJI_ADMIN_KEY") else {
+        return Ok(false);
+    };
+
+    let result = sqlx::query!(
+        "INSERT IGNORE INTO tb_api_keys (name, key_hash, scope) VALUES ('bootstrap-admin', ?, 'ADMIN')",
+        hash_key(&key)
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// POST /api/admin/keys - Create an API key, the secret is only shown once
+pub async fn create_api_key(
+    State(pool): State<AppState>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreateApiKeyResponse>, ApiError> {
+    if payload.name.is_empty() || payload.name.len() > 100 {
+        return Err(ApiError::validation("Key name must be between 1 and 100 characters"));
+    }
+
+    // An HMAC key still needs a unique key_hash, so it gets a bearer secret
+    // nobody is ever shown
+    let api_key = generate_secret("moji");
+    let hmac_secret = payload.hmac.then(|| generate_secret("mojihmac"));
+
+    let id = sqlx::query!(
+        "INSERT INTO tb_api_keys (name, key_hash, hmac_secret, scope) VALUES (?, ?, ?, ?)",
+        payload.name,
+        hash_key(&api_key),
+        hmac_secret,
+        payload.scope.as_db()
+    )
+    .execute(&pool.pool)
+    .await?
+    .last_insert_id();
+
+    tracing::info!("🔑 Created {:?} API key '{}' (id {})", payload.scope, payload.name, id);
+
+    Ok(Json(CreateApiKeyResponse {
+        id,
+        name: payload.name,
+        scope: payload.scope,
+        api_key: hmac_secret.is_none().then_some(api_key),
+        hmac_secret,
+    }))
+}
+
+// GET /api/admin/keys - List API keys without their secrets
+pub async fn list_api_keys(State(pool): State<AppState>) -> Result<Json<Vec<ApiKeyInfo>>, ApiError> {
+    let keys = sqlx::query_as!(
+        ApiKeyInfo,
+        r#"SELECT id, name, scope, is_active, (hmac_secret IS NOT NULL) as "uses_hmac!: i64", created_at, last_used_at
+         FROM tb_api_keys ORDER BY id"#
+    )
+    .fetch_all(&pool.pool)
+    .await?;
+
+    Ok(Json(keys))
+}
+
+// DELETE /api/admin/keys/{id} - Revoke an API key
+pub async fn revoke_api_key(
+    Path(id): Path<i32>,
+    State(pool): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let result = sqlx::query!("UPDATE tb_api_keys SET is_active = 0 WHERE id = ?", id)
+        .execute(&pool.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(format!("No API key with id {}", id)));
+    }
+
+    tracing::info!("🔑 Revoked API key {}", id);
+    Ok(Json(serde_json::json!({ "success": true, "message": format!("API key {} revoked", id) })))
+}
@@ -0,0 +1,218 @@
+// api/bank.rs
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::{
+    api::{error::ApiError, user::lock_user, validation::validate_player_uuid},
+    AppState,
+};
+
+/// Reasons the game plugin may give when it closes a session. `EXPIRED` is
+/// reserved for the server's own timeout sweep.
+const CLIENT_CLOSE_REASONS: &[&str] = &["CLOSED", "LOGOUT", "MOVED_AWAY"];
+
+#[derive(Debug, Serialize)]
+pub struct BankSession {
+    pub id: i64,
+    pub player_uuid: String,
+    pub opened_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub close_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloseBankRequest {
+    pub reason: Option<String>,
+}
+
+async fn get_active_session(conn: &mut MySqlConnection, uuid: &str) -> Result<Option<BankSession>, sqlx::Error> {
+    let session = sqlx::query_as!(
+        BankSession,
+        "SELECT id, player_uuid, opened_at, expires_at, closed_at, close_reason FROM tb_bank_sessions WHERE player_uuid = ? AND closed_at IS NULL ORDER BY id DESC LIMIT 1",
+        uuid
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(session)
+}
+
+async fn get_session(pool: &MySqlPool, id: u64) -> Result<Option<BankSession>, sqlx::Error> {
+    let session = sqlx::query_as!(
+        BankSession,
+        "SELECT id, player_uuid, opened_at, expires_at, closed_at, close_reason FROM tb_bank_sessions WHERE id = ?",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(session)
+}
+
+/// True while the player has an open session that hasn't passed its deadline.
+/// The expiry sweep runs behind `expires_at`, so moving bank money checks this
+/// rather than trusting `is_bank_open` alone.
+pub async fn has_live_session(conn: &mut MySqlConnection, uuid: &str) -> Result<bool, sqlx::Error> {
+    let record = sqlx::query!(
+        r#"SELECT EXISTS(
+            SELECT 1 FROM tb_bank_sessions WHERE player_uuid = ? AND closed_at IS NULL AND expires_at > NOW()
+         ) as "live!: i64""#,
+        uuid
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(record.live != 0)
+}
+
+/// Closes every session past its deadline and locks those players' banks,
+/// returning how many sessions were closed.
+pub async fn expire_bank_sessions(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Both statements use one cutoff, so every session closed here also has
+    // its player's bank locked
+    let cutoff = sqlx::query!(r#"SELECT NOW() as "now!: chrono::DateTime<chrono::Utc>""#)
+        .fetch_one(&mut *tx)
+        .await?
+        .now;
+
+    sqlx::query!(
+        "UPDATE tb_user u JOIN tb_bank_sessions s ON u.player_uuid = s.player_uuid
+         SET u.is_bank_open = 0
+         WHERE s.closed_at IS NULL AND s.expires_at <= ?",
+        cutoff
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Counted on its own: a multi-table UPDATE also counts the user rows
+    let result = sqlx::query!(
+        "UPDATE tb_bank_sessions SET closed_at = NOW(), close_reason = 'EXPIRED'
+         WHERE closed_at IS NULL AND expires_at <= ?",
+        cutoff
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+// POST /api/user/{uuid}/bank/open - Player starts using a bank, extends an open session
+pub async fn open_bank(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<BankSession>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    let timeout_secs = pool.config.current().bank_session_timeout_secs;
+
+    let mut tx = pool.pool.begin().await?;
+
+    if lock_user(&mut tx, &uuid).await?.is_none() {
+        return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+    }
+
+    let session_id = match get_active_session(&mut tx, &uuid).await? {
+        Some(session) => {
+            sqlx::query!(
+                "UPDATE tb_bank_sessions SET expires_at = DATE_ADD(NOW(), INTERVAL ? SECOND) WHERE id = ?",
+                timeout_secs,
+                session.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            session.id as u64
+        }
+        None => sqlx::query!(
+            "INSERT INTO tb_bank_sessions (player_uuid, expires_at) VALUES (?, DATE_ADD(NOW(), INTERVAL ? SECOND))",
+            uuid,
+            timeout_secs
+        )
+        .execute(&mut *tx)
+        .await?
+        .last_insert_id(),
+    };
+
+    sqlx::query!("UPDATE tb_user SET is_bank_open = 1 WHERE player_uuid = ?", uuid)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("🏦 Bank opened for {} (session {}, {}s)", uuid, session_id, timeout_secs);
+    get_session(&pool.pool, session_id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Bank session {} not found", session_id)))
+}
+
+// POST /api/user/{uuid}/bank/close - Player leaves the bank
+pub async fn close_bank(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+    payload: Option<Json<CloseBankRequest>>,
+) -> Result<Json<BankSession>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    let reason = payload
+        .and_then(|Json(p)| p.reason)
+        .map(|r| r.to_uppercase())
+        .unwrap_or_else(|| "CLOSED".to_string());
+    if !CLIENT_CLOSE_REASONS.contains(&reason.as_str()) {
+        return Err(ApiError::validation(format!(
+            "Invalid close reason. Use one of {}",
+            CLIENT_CLOSE_REASONS.join(", ")
+        )));
+    }
+
+    let mut tx = pool.pool.begin().await?;
+
+    if lock_user(&mut tx, &uuid).await?.is_none() {
+        return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+    }
+
+    let session = get_active_session(&mut tx, &uuid)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No open bank session for {}", uuid)))?;
+
+    sqlx::query!(
+        "UPDATE tb_bank_sessions SET closed_at = NOW(), close_reason = ? WHERE id = ?",
+        reason,
+        session.id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!("UPDATE tb_user SET is_bank_open = 0 WHERE player_uuid = ?", uuid)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("🏦 Bank closed for {} ({})", uuid, reason);
+    get_session(&pool.pool, session.id as u64)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Bank session {} not found", session.id)))
+}
+
+// GET /api/user/{uuid}/bank/session - The player's open bank session, if any
+pub async fn get_bank_session(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<BankSession>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    let mut conn = pool.pool.acquire().await?;
+    get_active_session(&mut conn, &uuid)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("No open bank session for {}", uuid)))
+}
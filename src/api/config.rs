@@ -1,71 +1,423 @@
 // api/config.rs
-use sqlx::MySqlPool;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
 use std::collections::HashMap;
-use bigdecimal::ToPrimitive;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use bigdecimal::{BigDecimal, ToPrimitive};
+
+use crate::{
+    api::{auth::ApiClient, error::ApiError, fees::load_fee_schedules},
+    fees::{FeeBracket, FeeOperation, FeeSchedule, FEE_ROUNDING},
+    money::{Money, MoneyOverflow, Rate},
+    AppState,
+};
+
+/// Allowed range for each key that can be edited through the admin API.
+const CONFIG_BOUNDS: &[(&str, f64, f64)] = &[
+    ("market_vat_rate", 0.0, 1.0),
+    ("transfer_fee_rate", 0.0, 1.0),
+    ("wallet_to_bank_fee_rate", 0.0, 1.0),
+    ("wallet_to_bank_threshold", 0.0, 999_999.0),
+    ("market_transaction_fee", 0.0, 1.0),
+    ("p2p_fee_rate", 0.0, 1.0),
+    ("config_refresh_interval_secs", 1.0, 86_400.0),
+    ("bank_session_timeout_secs", 10.0, 86_400.0),
+    ("regeneration_interval_secs", 60.0, 604_800.0),
+    ("regeneration_decay_rate", 0.0, 1.0),
+    ("regeneration_max_multiplier", 0.1, 100.0),
+    ("regeneration_volume_reference", 0.0, 1_000_000.0),
+    ("regeneration_paused", 0.0, 1.0),
+    ("leaderboard_cache_secs", 0.0, 86_400.0),
+    ("market_quote_ttl_secs", 5.0, 3_600.0),
+    ("idempotency_key_ttl_secs", 60.0, 2_592_000.0),
+];
+
 #[derive(Clone)]
 pub struct ConfigManager {
-    pub market_vat_rate: f64,
-    pub transfer_fee_rate: f64,
-    pub wallet_to_bank_fee_rate: f64,
-    pub wallet_to_bank_threshold: i64,
-    pub market_transaction_fee: f64,
+    pub market_vat_rate: Rate,
+    pub transfer_fee_rate: Rate,
+    pub wallet_to_bank_fee_rate: Rate,
+    pub wallet_to_bank_threshold: Money,
+    pub market_transaction_fee: Rate,
+    pub p2p_fee_rate: Rate,
+    pub config_refresh_interval_secs: u64,
+    pub bank_session_timeout_secs: i64,
+    pub regeneration_interval_secs: u64,
+    pub regeneration_decay_rate: f64,
+    pub regeneration_max_multiplier: f64,
+    pub regeneration_volume_reference: f64,
+    pub regeneration_paused: bool,
+    pub leaderboard_cache_secs: u64,
+    pub market_quote_ttl_secs: i64,
+    pub idempotency_key_ttl_secs: i64,
+    /// Rows of `tb_fee_schedules`, loaded and reloaded along with `tb_config`.
+    pub fee_schedules: Arc<HashMap<FeeOperation, FeeSchedule>>,
+}
+
+/// Shared, cached copy of `tb_config`. Handlers read a snapshot with
+/// `current()` and a reload swaps it for every clone of the handle.
+#[derive(Clone)]
+pub struct ConfigHandle {
+    inner: Arc<RwLock<ConfigManager>>,
+}
+
+
+#[derive(Debug, Serialize)]
+pub struct ConfigEntry {
+    pub config_key: String,
+    pub config_value: f64,
+    pub description: Option<String>,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigChange {
+    pub config_key: String,
+    pub old_value: f64,
+    pub new_value: f64,
+    pub changed_by: String,
+    pub changed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpdateConfigRequest {
+    pub value: f64,
+}
 
 #[derive(Debug)]
 pub struct MarketFees {
-    pub gross_amount: i64,
-    pub transaction_fee: i64,
-    pub vat: i64,
-    pub net_amount: i64,
+    pub gross_amount: Money,
+    pub transaction_fee: Money,
+    pub vat: Money,
+    pub net_amount: Money,
+}
+
+
+/// A stored DECIMAL as f64, failing like any other undecodable column.
+fn decimal_to_f64(column: &str, value: &BigDecimal) -> Result<f64, sqlx::Error> {
+    value
+        .to_f64()
+        .ok_or_else(|| sqlx::Error::Decode(format!("{} is not a number: {}", column, value).into()))
+}
+
+/// Every `tb_config` row as key/value pairs.
+async fn load_config_map(conn: &mut MySqlConnection) -> Result<HashMap<String, f64>, sqlx::Error> {
+    let configs = sqlx::query!(
+        "SELECT config_key, config_value FROM tb_config"
+    )
+    .fetch_all(conn)
+    .await?;
+
+    let mut config_map: HashMap<String, f64> = HashMap::new();
+    for config in configs {
+        let value = decimal_to_f64(&format!("tb_config.{}", config.config_key), &config.config_value)?;
+        config_map.insert(config.config_key, value);
+    }
+    Ok(config_map)
+}
+
+fn rate(config_map: &HashMap<String, f64>, key: &str, default: f64) -> Result<Rate, String> {
+    let value = *config_map.get(key).unwrap_or(&default);
+    Rate::from_fraction(value).ok_or_else(|| format!("{} is not a valid rate: {}", key, value))
 }
 
+fn whole(config_map: &HashMap<String, f64>, key: &str, default: f64) -> Result<f64, String> {
+    let value = *config_map.get(key).unwrap_or(&default);
+    if value.fract() != 0.0 {
+        return Err(format!("{} must be a whole number: {}", key, value));
+    }
+    Ok(value)
+}
 
 impl ConfigManager {
     pub async fn load_from_db(pool: &MySqlPool) -> Result<Self, sqlx::Error> {
-        let configs = sqlx::query!(
-            "SELECT config_key, config_value FROM tb_config"
-        )
-        .fetch_all(pool)
-        .await?;
-
-        let mut config_map: HashMap<String, f64> = HashMap::new();
-        for config in configs {
-            config_map.insert(config.config_key, config.config_value.to_f64().unwrap_or(0.0));
+        let config_map = load_config_map(&mut *pool.acquire().await?).await?;
+
+        // A bad row stops the load instead of quietly becoming a 0% rate
+        let mut config = Self::from_map(&config_map).map_err(|e| sqlx::Error::Decode(e.into()))?;
+        config.fee_schedules = Arc::new(load_fee_schedules(pool).await?);
+        Ok(config)
+    }
+
+    /// Builds a config from `tb_config`-style key/value pairs, defaulting
+    /// missing keys. Values outside `CONFIG_BOUNDS` are rejected. No custom
+    /// fee schedules are set.
+    pub fn from_map(config_map: &HashMap<String, f64>) -> Result<Self, String> {
+        for (key, value) in config_map {
+            let Some((_, min, max)) = CONFIG_BOUNDS.iter().find(|(k, _, _)| k == key) else {
+                continue;
+            };
+            if !value.is_finite() || value < min || value > max {
+                return Err(format!("{} must be between {} and {}, got {}", key, min, max, value));
+            }
         }
 
         Ok(ConfigManager {
-            market_vat_rate: *config_map.get("market_vat_rate").unwrap_or(&0.34),
-            transfer_fee_rate: *config_map.get("transfer_fee_rate").unwrap_or(&0.10),
-            wallet_to_bank_fee_rate: *config_map.get("wallet_to_bank_fee_rate").unwrap_or(&0.05),
-            wallet_to_bank_threshold: *config_map.get("wallet_to_bank_threshold").unwrap_or(&10000.0) as i64,
-            market_transaction_fee: *config_map.get("market_transaction_fee").unwrap_or(&0.02),
+            market_vat_rate: rate(config_map, "market_vat_rate", 0.34)?,
+            transfer_fee_rate: rate(config_map, "transfer_fee_rate", 0.10)?,
+            wallet_to_bank_fee_rate: rate(config_map, "wallet_to_bank_fee_rate", 0.05)?,
+            wallet_to_bank_threshold: Money::new(whole(config_map, "wallet_to_bank_threshold", 10000.0)? as i64),
+            market_transaction_fee: rate(config_map, "market_transaction_fee", 0.02)?,
+            p2p_fee_rate: rate(config_map, "p2p_fee_rate", 0.05)?,
+            config_refresh_interval_secs: *config_map.get("config_refresh_interval_secs").unwrap_or(&60.0) as u64,
+            bank_session_timeout_secs: *config_map.get("bank_session_timeout_secs").unwrap_or(&300.0) as i64,
+            regeneration_interval_secs: *config_map.get("regeneration_interval_secs").unwrap_or(&10800.0) as u64,
+            regeneration_decay_rate: *config_map.get("regeneration_decay_rate").unwrap_or(&0.1),
+            regeneration_max_multiplier: *config_map.get("regeneration_max_multiplier").unwrap_or(&4.0),
+            regeneration_volume_reference: *config_map.get("regeneration_volume_reference").unwrap_or(&640.0),
+            regeneration_paused: *config_map.get("regeneration_paused").unwrap_or(&0.0) > 0.0,
+            leaderboard_cache_secs: *config_map.get("leaderboard_cache_secs").unwrap_or(&60.0) as u64,
+            market_quote_ttl_secs: *config_map.get("market_quote_ttl_secs").unwrap_or(&30.0) as i64,
+            idempotency_key_ttl_secs: *config_map.get("idempotency_key_ttl_secs").unwrap_or(&86400.0) as i64,
+            fee_schedules: Arc::default(),
         })
     }
 
-    pub fn calculate_transfer_fee(&self, from: &str, to: &str, amount: i64) -> i64 {
-        match (from, to) {
-            ("wallet", "bank") if amount >= self.wallet_to_bank_threshold => {
-                (amount as f64 * self.wallet_to_bank_fee_rate) as i64
-            }
-            ("wallet", "bank") | ("bank", "wallet") => {
-                (amount as f64 * self.transfer_fee_rate) as i64
+    /// The schedule for `operation`: its `tb_fee_schedules` row, else the default.
+    pub fn fee_schedule(&self, operation: FeeOperation) -> FeeSchedule {
+        self.fee_schedules
+            .get(&operation)
+            .cloned()
+            .unwrap_or_else(|| self.default_fee_schedule(operation))
+    }
+
+    /// The schedule used for `operation` when `tb_fee_schedules` has none,
+    /// built from the rates in `tb_config`.
+    pub fn default_fee_schedule(&self, operation: FeeOperation) -> FeeSchedule {
+        match operation {
+            FeeOperation::WalletToBank => {
+                let mut schedule = FeeSchedule::flat_rate(self.transfer_fee_rate);
+                if self.wallet_to_bank_threshold > Money::ZERO {
+                    schedule.brackets.push(FeeBracket {
+                        from: self.wallet_to_bank_threshold,
+                        flat: Money::ZERO,
+                        rate: self.wallet_to_bank_fee_rate,
+                    });
+                } else {
+                    schedule.brackets[0].rate = self.wallet_to_bank_fee_rate;
+                }
+                schedule
             }
-            _ => 0,
+            FeeOperation::BankToWallet => FeeSchedule::flat_rate(self.transfer_fee_rate),
+            FeeOperation::MarketSell | FeeOperation::MarketBuy => FeeSchedule::flat_rate(self.market_transaction_fee),
+            FeeOperation::P2p => FeeSchedule::flat_rate(self.p2p_fee_rate),
         }
     }
 
-    pub fn calculate_market_fees(&self, gross_amount: i64) -> MarketFees {
-        let transaction_fee = (gross_amount as f64 * self.market_transaction_fee) as i64;
-        let taxable_amount = gross_amount - transaction_fee;
-        let vat = (taxable_amount as f64 * self.market_vat_rate) as i64;
-        let net_amount = gross_amount - transaction_fee - vat;
+    /// Fees for a market sale. The transaction fee comes from `schedule` and
+    /// never exceeds the sale, VAT is charged on what is left.
+    pub fn calculate_market_fees(&self, schedule: &FeeSchedule, gross_amount: Money) -> Result<MarketFees, MoneyOverflow> {
+        let transaction_fee = schedule.fee(gross_amount)?.min(gross_amount);
+        let taxable_amount = gross_amount.checked_sub(transaction_fee)?;
+        let vat = self.market_vat_rate.apply(taxable_amount, FEE_ROUNDING)?;
+        let net_amount = taxable_amount.checked_sub(vat)?;
 
-        MarketFees {
+        Ok(MarketFees {
             gross_amount,
             transaction_fee,
             vat,
             net_amount,
+        })
+    }
+
+    /// Fees for a market purchase. `net_amount` is the total debited from the
+    /// buyer: gross + transaction fee + VAT on both.
+    pub fn calculate_market_buy_fees(&self, schedule: &FeeSchedule, gross_amount: Money) -> Result<MarketFees, MoneyOverflow> {
+        let transaction_fee = schedule.fee(gross_amount)?;
+        let taxable_amount = gross_amount.checked_add(transaction_fee)?;
+        let vat = self.market_vat_rate.apply(taxable_amount, FEE_ROUNDING)?;
+        let net_amount = taxable_amount.checked_add(vat)?;
+
+        Ok(MarketFees {
+            gross_amount,
+            transaction_fee,
+            vat,
+            net_amount,
+        })
+    }
+}
+
+impl ConfigHandle {
+    pub fn new(config: ConfigManager) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(config)),
         }
     }
+
+    pub fn current(&self) -> ConfigManager {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub async fn reload(&self, pool: &MySqlPool) -> Result<ConfigManager, sqlx::Error> {
+        let config = ConfigManager::load_from_db(pool).await?;
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = config.clone();
+        Ok(config)
+    }
+}
+
+// POST /api/admin/config/reload - Reload tb_config without restarting
+pub async fn reload_config(State(pool): State<AppState>) -> Result<Json<serde_json::Value>, ApiError> {
+    let config = pool.config.reload(&pool.pool).await?;
+    tracing::info!("🔄 Configuration reloaded by admin request");
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "market_vat_rate": config.market_vat_rate,
+        "transfer_fee_rate": config.transfer_fee_rate,
+        "wallet_to_bank_fee_rate": config.wallet_to_bank_fee_rate,
+        "wallet_to_bank_threshold": config.wallet_to_bank_threshold,
+        "market_transaction_fee": config.market_transaction_fee,
+        "p2p_fee_rate": config.p2p_fee_rate,
+    })))
+}
+
+fn validate_config_value(key: &str, value: f64) -> Result<BigDecimal, ApiError> {
+    let Some((_, min, max)) = CONFIG_BOUNDS.iter().find(|(k, _, _)| *k == key) else {
+        return Err(ApiError::validation(format!("{} cannot be edited through the API", key)));
+    };
+    if !value.is_finite() || value < *min || value > *max {
+        return Err(ApiError::validation(format!("{} must be between {} and {}", key, min, max)));
+    }
+
+    // tb_config stores DECIMAL(10,4)
+    BigDecimal::from_str(&format!("{:.4}", value))
+        .map_err(|_| ApiError::validation(format!("Invalid value for {}", key)))
+}
+
+async fn get_config_entries(pool: &MySqlPool) -> Result<Vec<ConfigEntry>, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT config_key, config_value, description, updated_by, updated_at FROM tb_config ORDER BY config_key"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|r| {
+            Ok(ConfigEntry {
+                config_value: decimal_to_f64(&format!("tb_config.{}", r.config_key), &r.config_value)?,
+                config_key: r.config_key,
+                description: r.description,
+                updated_by: r.updated_by,
+                updated_at: r.updated_at,
+            })
+        })
+        .collect()
+}
+
+async fn get_config_history(pool: &MySqlPool, key: &str) -> Result<Vec<ConfigChange>, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT config_key, old_value, new_value, changed_by, changed_at FROM tb_config_audit WHERE config_key = ? ORDER BY id DESC LIMIT 100",
+        key
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|r| {
+            let column = format!("tb_config_audit.{}", r.config_key);
+            Ok(ConfigChange {
+                old_value: decimal_to_f64(&column, &r.old_value)?,
+                new_value: decimal_to_f64(&column, &r.new_value)?,
+                config_key: r.config_key,
+                changed_by: r.changed_by,
+                changed_at: r.changed_at,
+            })
+        })
+        .collect()
+}
+
+// GET /api/admin/config - List every config value
+pub async fn list_config(State(pool): State<AppState>) -> Result<Json<Vec<ConfigEntry>>, ApiError> {
+    Ok(Json(get_config_entries(&pool.pool).await?))
+}
+
+// GET /api/admin/config/{key} - Read a single config value
+pub async fn get_config(
+    Path(key): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<ConfigEntry>, ApiError> {
+    get_config_entries(&pool.pool)
+        .await?
+        .into_iter()
+        .find(|entry| entry.config_key == key)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("Unknown config key {}", key)))
+}
+
+// GET /api/admin/config/{key}/history - Who changed a config value and when
+pub async fn get_config_changes(
+    Path(key): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<Vec<ConfigChange>>, ApiError> {
+    Ok(Json(get_config_history(&pool.pool, &key).await?))
+}
+
+/// Validates and stores a config value, records who changed it and reloads
+/// the shared config so the change applies immediately.
+pub async fn set_config_value(state: &AppState, key: &str, value: f64, changed_by: &str) -> Result<(), ApiError> {
+    let new_value = validate_config_value(key, value)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let current = sqlx::query!(
+        "SELECT config_value FROM tb_config WHERE config_key = ? FOR UPDATE",
+        key
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Unknown config key {}", key)))?;
+
+    // Check the whole config as it would load, so a value that passes its
+    // bounds but can't be used (e.g. a fractional threshold) is never stored
+    let mut candidate = load_config_map(&mut tx).await?;
+    candidate.insert(key.to_string(), decimal_to_f64(key, &new_value)?);
+    ConfigManager::from_map(&candidate).map_err(ApiError::validation)?;
+
+    sqlx::query!(
+        "UPDATE tb_config SET config_value = ?, updated_by = ? WHERE config_key = ?",
+        new_value,
+        changed_by,
+        key
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO tb_config_audit (config_key, old_value, new_value, changed_by) VALUES (?, ?, ?, ?)",
+        key,
+        current.config_value,
+        new_value,
+        changed_by
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    state.config.reload(&state.pool).await?;
+    tracing::info!(
+        "⚙️ {} changed {} from {} to {}",
+        changed_by, key, current.config_value, new_value
+    );
+
+    Ok(())
+}
+
+// PUT /api/admin/config/{key} - Update a config value and apply it immediately
+pub async fn update_config(
+    Path(key): Path<String>,
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+    Json(payload): Json<UpdateConfigRequest>,
+) -> Result<Json<ConfigEntry>, ApiError> {
+    set_config_value(&pool, &key, payload.value, &client.name).await?;
+
+    get_config(Path(key), State(pool)).await
 }
@@ -0,0 +1,120 @@
+// api/error.rs
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+
+use crate::money::MoneyOverflow;
+
+/// Error returned by every handler. Rendered as `{ "code": ..., "message": ... }`.
+#[derive(Debug)]
+pub enum ApiError {
+    Validation(String),
+    NotFound(String),
+    ItemUnavailable(String),
+    InsufficientFunds { source: String, have: i64, need: i64 },
+    BankClosed,
+    /// The price moved past the limit the player accepted.
+    PriceMoved { limit: i64, current: i64 },
+    Conflict(String),
+    Unauthorized(String),
+    Forbidden(String),
+    Database(sqlx::Error),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        ApiError::Validation(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        ApiError::NotFound(message.into())
+    }
+
+    /// What the client sees, also used to report errors per line of a batch.
+    pub fn body(&self) -> ErrorBody {
+        ErrorBody {
+            code: self.code(),
+            message: self.message(),
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::ItemUnavailable(_) => StatusCode::NOT_FOUND,
+            ApiError::InsufficientFunds { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::BankClosed => StatusCode::FORBIDDEN,
+            ApiError::PriceMoved { .. } => StatusCode::CONFLICT,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            ApiError::Validation(_) => "VALIDATION_ERROR",
+            ApiError::NotFound(_) => "NOT_FOUND",
+            ApiError::ItemUnavailable(_) => "ITEM_UNAVAILABLE",
+            ApiError::InsufficientFunds { .. } => "INSUFFICIENT_FUNDS",
+            ApiError::BankClosed => "BANK_CLOSED",
+            ApiError::PriceMoved { .. } => "PRICE_MOVED",
+            ApiError::Conflict(_) => "CONFLICT",
+            ApiError::Unauthorized(_) => "UNAUTHORIZED",
+            ApiError::Forbidden(_) => "FORBIDDEN",
+            ApiError::Database(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ApiError::Validation(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message) => message.clone(),
+            ApiError::ItemUnavailable(item_key) => format!("Item {} not available in market", item_key),
+            ApiError::InsufficientFunds { source, have, need } => {
+                format!("Insufficient funds in {} (have: {}, need: {})", source, have, need)
+            }
+            ApiError::BankClosed => "Bank is not open! Visit a bank to access your account".to_string(),
+            ApiError::PriceMoved { limit, current } => {
+                format!("Price moved beyond your limit (limit: {}, current: {})", limit, current)
+            }
+            // Don't leak query details to clients, they're logged instead
+            ApiError::Database(_) => "Internal server error".to_string(),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        ApiError::Database(e)
+    }
+}
+
+impl From<MoneyOverflow> for ApiError {
+    fn from(_: MoneyOverflow) -> Self {
+        ApiError::validation("Amount is too large")
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Database(e) = &self {
+            tracing::error!("Database error: {:?}", e);
+        }
+
+        (self.status(), Json(self.body())).into_response()
+    }
+}
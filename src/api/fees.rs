@@ -0,0 +1,231 @@
+// api/fees.rs
+use axum::{
+    extract::{Extension, Path, Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+
+use crate::{
+    api::{
+        auth::ApiClient,
+        error::ApiError,
+        market::get_market_item,
+        validation::{trade_total, validate_amount, validate_item_key, validate_quantity},
+    },
+    fees::{FeeOperation, FeeSchedule},
+    money::Money,
+    AppState,
+};
+
+#[derive(Debug, Serialize)]
+pub struct FeeScheduleEntry {
+    pub operation: FeeOperation,
+    pub schedule: FeeSchedule,
+    /// False when the schedule is the default built from `tb_config`.
+    pub custom: bool,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteQuery {
+    pub operation: String,
+    pub amount: Option<i64>,
+    pub item_key: Option<String>,
+    pub quantity: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeQuote {
+    pub operation: FeeOperation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_unit: Option<i64>,
+    pub amount: i64,
+    pub fee: i64,
+    pub vat: i64,
+    /// Leaves the player's account: the amount plus fees, or 0 for a sale.
+    pub total_debited: i64,
+    /// Arrives in the receiving account: the amount, the sale's net, or 0 for a purchase.
+    pub total_credited: i64,
+}
+
+fn parse_operation(operation: &str) -> Result<FeeOperation, ApiError> {
+    FeeOperation::parse(operation).ok_or_else(|| {
+        let names: Vec<&str> = FeeOperation::ALL.iter().map(|o| o.as_str()).collect();
+        ApiError::validation(format!("Unknown fee operation {}, expected one of {}", operation, names.join(", ")))
+    })
+}
+
+fn parse_schedule(operation: &str, config: &str) -> Option<FeeSchedule> {
+    let schedule = serde_json::from_str::<FeeSchedule>(config).map_err(|e| e.to_string());
+    match schedule.and_then(|s| s.validate().map(|_| s)) {
+        Ok(schedule) => Some(schedule),
+        Err(e) => {
+            tracing::error!("Ignoring invalid fee schedule for {}: {}", operation, e);
+            None
+        }
+    }
+}
+
+/// Every valid `tb_fee_schedules` row, for `ConfigManager` to cache. Invalid
+/// rows are logged and left out, so their operation uses the default.
+pub async fn load_fee_schedules(pool: &MySqlPool) -> Result<HashMap<FeeOperation, FeeSchedule>, sqlx::Error> {
+    let rows = sqlx::query!("SELECT operation, config FROM tb_fee_schedules")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|r| {
+            let Some(operation) = FeeOperation::parse(&r.operation) else {
+                tracing::error!("Ignoring fee schedule for unknown operation {}", r.operation);
+                return None;
+            };
+            parse_schedule(&r.operation, &r.config).map(|schedule| (operation, schedule))
+        })
+        .collect())
+}
+
+// GET /api/fees/quote - Exact fee for an operation, before the player confirms it
+pub async fn get_fee_quote(
+    State(pool): State<AppState>,
+    Query(query): Query<QuoteQuery>,
+) -> Result<Json<FeeQuote>, ApiError> {
+    let operation = parse_operation(&query.operation)?;
+    let config = pool.config.current();
+
+    // Market quotes can price the trade at the item's current price
+    let mut price_per_unit = None;
+    let amount = match (query.amount, &query.item_key) {
+        (Some(amount), None) => {
+            validate_amount(amount)?;
+            Money::new(amount)
+        }
+        (None, Some(item_key)) if matches!(operation, FeeOperation::MarketSell | FeeOperation::MarketBuy) => {
+            validate_item_key(item_key)?;
+            let quantity = query.quantity.ok_or_else(|| ApiError::validation("quantity is required with item_key"))?;
+            validate_quantity(quantity)?;
+
+            let item = get_market_item(&pool.pool, item_key)
+                .await?
+                .ok_or_else(|| ApiError::ItemUnavailable(item_key.clone()))?;
+            let price = if operation == FeeOperation::MarketSell {
+                item.current_sell_price
+            } else {
+                item.current_buy_price
+            };
+            price_per_unit = Some(price);
+            trade_total(price, quantity)?
+        }
+        (None, Some(_)) => return Err(ApiError::validation("item_key is only accepted for market quotes")),
+        _ => return Err(ApiError::validation("Pass either amount or item_key and quantity")),
+    };
+
+    let schedule = config.fee_schedule(operation);
+
+    let (fee, vat, total_debited, total_credited) = match operation {
+        FeeOperation::MarketSell => {
+            let fees = config.calculate_market_fees(&schedule, amount)?;
+            (fees.transaction_fee, fees.vat, Money::ZERO, fees.net_amount)
+        }
+        FeeOperation::MarketBuy => {
+            let fees = config.calculate_market_buy_fees(&schedule, amount)?;
+            (fees.transaction_fee, fees.vat, fees.net_amount, Money::ZERO)
+        }
+        FeeOperation::WalletToBank | FeeOperation::BankToWallet | FeeOperation::P2p => {
+            let fee = schedule.fee(amount)?;
+            (fee, Money::ZERO, amount.checked_add(fee)?, amount)
+        }
+    };
+
+    Ok(Json(FeeQuote {
+        operation,
+        item_key: price_per_unit.and(query.item_key),
+        quantity: price_per_unit.and(query.quantity),
+        price_per_unit,
+        amount: amount.amount(),
+        fee: fee.amount(),
+        vat: vat.amount(),
+        total_debited: total_debited.amount(),
+        total_credited: total_credited.amount(),
+    }))
+}
+
+// GET /api/admin/fees - The schedule in effect for every operation
+pub async fn list_fee_schedules(State(pool): State<AppState>) -> Result<Json<Vec<FeeScheduleEntry>>, ApiError> {
+    let config = pool.config.current();
+    let rows = sqlx::query!("SELECT operation, config, updated_by, updated_at FROM tb_fee_schedules")
+        .fetch_all(&pool.pool)
+        .await?;
+
+    let entries = FeeOperation::ALL
+        .into_iter()
+        .map(|operation| {
+            let row = rows.iter().find(|r| r.operation == operation.as_str());
+            let schedule = row.and_then(|r| parse_schedule(&r.operation, &r.config));
+            FeeScheduleEntry {
+                operation,
+                custom: schedule.is_some(),
+                schedule: schedule.unwrap_or_else(|| config.default_fee_schedule(operation)),
+                updated_by: row.and_then(|r| r.updated_by.clone()),
+                updated_at: row.and_then(|r| r.updated_at),
+            }
+        })
+        .collect();
+
+    Ok(Json(entries))
+}
+
+// PUT /api/admin/fees/{operation} - Replace the fee schedule for an operation
+pub async fn set_fee_schedule(
+    Path(operation): Path<String>,
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+    Json(payload): Json<FeeSchedule>,
+) -> Result<Json<FeeSchedule>, ApiError> {
+    let operation = parse_operation(&operation)?;
+    payload.validate().map_err(ApiError::validation)?;
+
+    let config = serde_json::to_string(&payload)
+        .map_err(|e| ApiError::validation(format!("Invalid fee schedule: {}", e)))?;
+
+    sqlx::query!(
+        "INSERT INTO tb_fee_schedules (operation, config, updated_by) VALUES (?, ?, ?)
+         ON DUPLICATE KEY UPDATE config = VALUES(config), updated_by = VALUES(updated_by)",
+        operation.as_str(),
+        config,
+        client.name
+    )
+    .execute(&pool.pool)
+    .await?;
+    pool.config.reload(&pool.pool).await?;
+
+    tracing::info!("💸 {} set the {} fee schedule to {}", client.name, operation, config);
+    Ok(Json(payload))
+}
+
+// DELETE /api/admin/fees/{operation} - Fall back to the tb_config rates
+pub async fn delete_fee_schedule(
+    Path(operation): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let operation = parse_operation(&operation)?;
+
+    let result = sqlx::query!("DELETE FROM tb_fee_schedules WHERE operation = ?", operation.as_str())
+        .execute(&pool.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(format!("No fee schedule set for {}", operation)));
+    }
+    pool.config.reload(&pool.pool).await?;
+
+    tracing::info!("💸 Removed the {} fee schedule", operation);
+    Ok(Json(serde_json::json!({ "success": true, "message": format!("Removed fee schedule for {}", operation) })))
+}
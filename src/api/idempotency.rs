@@ -0,0 +1,238 @@
+// api/idempotency.rs
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use tokio::time::{interval, Duration};
+
+use crate::{
+    api::{auth::ApiClient, error::ApiError},
+    AppState,
+};
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on a response that was stored by an earlier request with the same key.
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+/// Largest body we'll buffer to fingerprint a request.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// How long a claim stays locked without being renewed. The running request
+/// renews it every third of this, so it only lapses if the server died and
+/// left the outcome unknown.
+const CLAIM_LEASE_SECS: i64 = 30;
+
+/// Fingerprint of a request, so a key can't be reused for a different one.
+fn request_hash(method: &str, path: &str, body: &Bytes) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn replay(status_code: u16, body: Vec<u8>) -> Response {
+    let status = StatusCode::from_u16(status_code).unwrap_or(StatusCode::OK);
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Reserves the key for this request. Returns the stored response when the
+/// key was already used for the same request.
+async fn claim_key(
+    pool: &MySqlPool,
+    client_id: i32,
+    key: &str,
+    hash: &str,
+    ttl_secs: i64,
+) -> Result<Option<Response>, ApiError> {
+    // Second pass only runs after an expired row was cleared
+    for _ in 0..2 {
+        let inserted = sqlx::query!(
+            "INSERT IGNORE INTO tb_idempotency_keys (client_id, idempotency_key, request_hash, locked_until, expires_at)
+             VALUES (?, ?, ?, DATE_ADD(NOW(), INTERVAL ? SECOND), DATE_ADD(NOW(), INTERVAL ? SECOND))",
+            client_id,
+            key,
+            hash,
+            CLAIM_LEASE_SECS,
+            ttl_secs
+        )
+        .execute(pool)
+        .await?;
+        if inserted.rows_affected() > 0 {
+            return Ok(None);
+        }
+
+        let Some(existing) = sqlx::query!(
+            r#"SELECT id, request_hash, status_code, response_body,
+                expires_at <= NOW() as "expired!: i64",
+                (locked_until IS NULL OR locked_until <= NOW()) as "lease_lapsed!: i64"
+             FROM tb_idempotency_keys WHERE client_id = ? AND idempotency_key = ?"#,
+            client_id,
+            key
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            continue;
+        };
+
+        if existing.expired != 0 {
+            sqlx::query!(
+                "DELETE FROM tb_idempotency_keys WHERE id = ? AND expires_at <= NOW()",
+                existing.id
+            )
+            .execute(pool)
+            .await?;
+            continue;
+        }
+        if existing.request_hash != hash {
+            return Err(ApiError::validation("Idempotency-Key was already used for a different request"));
+        }
+        let Some(status_code) = existing.status_code else {
+            // The claim isn't part of the handler's transaction, so a request
+            // that died may still have committed: never run it a second time
+            if existing.lease_lapsed != 0 {
+                tracing::warn!("⚠️ Idempotency-Key {} was claimed by a request that never finished", key);
+                return Err(ApiError::Conflict(
+                    "A request with this Idempotency-Key did not finish and its outcome is unknown".to_string(),
+                ));
+            }
+            return Err(ApiError::Conflict("A request with this Idempotency-Key is still in progress".to_string()));
+        };
+
+        tracing::info!("🔁 Replaying stored response for Idempotency-Key {}", key);
+        return Ok(Some(replay(status_code, existing.response_body.unwrap_or_default())));
+    }
+
+    Err(ApiError::Conflict("Idempotency-Key is being reused concurrently, try again".to_string()))
+}
+
+async fn renew_lease(pool: &MySqlPool, client_id: i32, key: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE tb_idempotency_keys SET locked_until = DATE_ADD(NOW(), INTERVAL ? SECOND)
+         WHERE client_id = ? AND idempotency_key = ? AND status_code IS NULL",
+        CLAIM_LEASE_SECS,
+        client_id,
+        key
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Keeps the response for retries. Server errors are kept too: a handler can
+/// fail after its transaction committed, so a retry must not run it again.
+async fn finish_key(pool: &MySqlPool, client_id: i32, key: &str, status: StatusCode, body: &Bytes) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE tb_idempotency_keys SET status_code = ?, response_body = ? WHERE client_id = ? AND idempotency_key = ?",
+        status.as_u16(),
+        body.as_ref(),
+        client_id,
+        key
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Makes a request with an `Idempotency-Key` header run at most once per API
+/// client: a retry with the same key and body gets the original response back.
+/// Requests without the header run as usual.
+pub async fn idempotency(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+        .ok_or_else(|| ApiError::validation(format!("Idempotency-Key must be 1 to {} visible characters", MAX_KEY_LEN)))?
+        .to_string();
+    let client_id = request
+        .extensions()
+        .get::<ApiClient>()
+        .map(|client| client.id)
+        .ok_or_else(|| ApiError::Unauthorized("Missing credentials".to_string()))?;
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| ApiError::validation("Request body too large"))?;
+    let path = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let hash = request_hash(parts.method.as_str(), path, &body);
+
+    let ttl_secs = state.config.current().idempotency_key_ttl_secs;
+    if let Some(stored) = claim_key(&state.pool, client_id, &key, &hash, ttl_secs).await? {
+        return Ok(stored);
+    }
+
+    // Runs on its own task so a client hanging up can't strand the claim:
+    // the request still finishes and its response is stored for the retry
+    let pool = state.pool.clone();
+    let request = Request::from_parts(parts, Body::from(body));
+    let work = tokio::spawn(async move {
+        let handler = next.run(request);
+        tokio::pin!(handler);
+        let mut renew = interval(Duration::from_secs(CLAIM_LEASE_SECS as u64 / 3));
+        renew.tick().await;
+        let response = loop {
+            tokio::select! {
+                response = &mut handler => break response,
+                _ = renew.tick() => {
+                    if let Err(e) = renew_lease(&pool, client_id, &key).await {
+                        tracing::error!("Failed to renew claim on Idempotency-Key {}: {:?}", key, e);
+                    }
+                }
+            }
+        };
+        store_response(&pool, client_id, &key, response).await
+    });
+
+    Ok(work.await.unwrap_or_else(|e| {
+        tracing::error!("Request with an Idempotency-Key failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    }))
+}
+
+/// Buffers the handler's response and records it against the key.
+async fn store_response(pool: &MySqlPool, client_id: i32, key: &str, response: Response) -> Response {
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for Idempotency-Key {}: {}", key, e);
+            let _ = finish_key(pool, client_id, key, StatusCode::INTERNAL_SERVER_ERROR, &Bytes::new()).await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // The request already ran, so a failure here is logged rather than returned
+    if let Err(e) = finish_key(pool, client_id, key, parts.status, &body).await {
+        tracing::error!("Failed to store response for Idempotency-Key {}: {:?}", key, e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Drops keys past their TTL, returning how many were removed.
+pub async fn purge_expired_idempotency_keys(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM tb_idempotency_keys WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
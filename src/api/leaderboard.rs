@@ -0,0 +1,420 @@
+// api/leaderboard.rs
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::{
+    api::{auth::ApiClient, error::ApiError, user::get_user_by_uuid, validation::validate_player_uuid},
+    AppState,
+};
+
+/// Rows kept per board, pages past this aren't served.
+const MAX_ENTRIES: i64 = 1000;
+const DEFAULT_PAGE_SIZE: usize = 10;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WealthBy {
+    Wallet,
+    Bank,
+    #[default]
+    Total,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum Window {
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+    #[default]
+    #[serde(rename = "all")]
+    AllTime,
+}
+
+impl WealthBy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WealthBy::Wallet => "wallet",
+            WealthBy::Bank => "bank",
+            WealthBy::Total => "total",
+        }
+    }
+}
+
+impl Window {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Window::Day => "24h",
+            Window::Week => "7d",
+            Window::AllTime => "all",
+        }
+    }
+
+    fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Window::Day => Some(now - chrono::Duration::hours(24)),
+            Window::Week => Some(now - chrono::Duration::days(7)),
+            Window::AllTime => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PlayerBoard {
+    Wealth(WealthBy),
+    Sellers(Window),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    /// Wealth board only.
+    #[serde(default)]
+    pub by: WealthBy,
+    /// Seller and item boards only.
+    #[serde(default)]
+    pub window: Window,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerRank {
+    pub rank: usize,
+    pub player_uuid: String,
+    pub player_name: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemRank {
+    pub rank: usize,
+    pub item_key: String,
+    pub item_name: String,
+    pub quantity: i64,
+    pub gross: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderboardPage<T> {
+    pub board: String,
+    /// When the board was computed. It can be up to `leaderboard_cache_secs` old.
+    pub generated_at: DateTime<Utc>,
+    pub total_entries: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub entries: Vec<T>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExcludedPlayer {
+    pub player_uuid: String,
+    pub player_name: String,
+}
+
+struct Cached<V> {
+    fetched: Instant,
+    generated_at: DateTime<Utc>,
+    value: Arc<V>,
+}
+
+/// Computed values shared by every request until they are older than the TTL.
+struct TtlCache<K, V> {
+    inner: RwLock<HashMap<K, Cached<V>>>,
+}
+
+impl<K: Eq + Hash, V> TtlCache<K, V> {
+    fn new() -> Self {
+        Self {
+            inner: RwLock::new(HashMap::new()),
+        }
+    }
+
+    async fn get_or_load<F, Fut>(&self, key: K, ttl: Duration, load: F) -> Result<(DateTime<Utc>, Arc<V>), sqlx::Error>
+    where
+        F: FnOnce(DateTime<Utc>) -> Fut,
+        Fut: Future<Output = Result<V, sqlx::Error>>,
+    {
+        {
+            let cache = self.inner.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(cached) = cache.get(&key).filter(|c| c.fetched.elapsed() < ttl) {
+                return Ok((cached.generated_at, cached.value.clone()));
+            }
+        }
+
+        // Not locked while loading, a concurrent miss just loads the board twice
+        let generated_at = Utc::now();
+        let value = Arc::new(load(generated_at).await?);
+        let cached = Cached {
+            fetched: Instant::now(),
+            generated_at,
+            value: value.clone(),
+        };
+        self.inner.write().unwrap_or_else(|e| e.into_inner()).insert(key, cached);
+
+        Ok((generated_at, value))
+    }
+
+    fn clear(&self) {
+        self.inner.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// Cached leaderboards, refreshed at most every `leaderboard_cache_secs`.
+#[derive(Clone)]
+pub struct LeaderboardCache {
+    players: Arc<TtlCache<PlayerBoard, Vec<PlayerRank>>>,
+    items: Arc<TtlCache<Window, Vec<ItemRank>>>,
+}
+
+impl LeaderboardCache {
+    pub fn new() -> Self {
+        Self {
+            players: Arc::new(TtlCache::new()),
+            items: Arc::new(TtlCache::new()),
+        }
+    }
+
+    /// Drops every cached board so the next request recomputes it.
+    pub fn clear(&self) {
+        self.players.clear();
+        self.items.clear();
+    }
+}
+
+impl Default for LeaderboardCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn ranked<T>(rows: impl IntoIterator<Item = T>, build: impl Fn(usize, T) -> PlayerRank) -> Vec<PlayerRank> {
+    rows.into_iter().enumerate().map(|(i, row)| build(i + 1, row)).collect()
+}
+
+async fn fetch_wealth(pool: &MySqlPool, by: WealthBy) -> Result<Vec<PlayerRank>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT player_uuid, player_name,
+            CAST(CASE ? WHEN 'wallet' THEN wallet WHEN 'bank' THEN bank ELSE wallet + bank END AS SIGNED) as "value!: i64"
+         FROM tb_user
+         WHERE exclude_from_leaderboards = 0
+         ORDER BY 3 DESC, id
+         LIMIT ?"#,
+        by.as_str(),
+        MAX_ENTRIES
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ranked(rows, |rank, r| PlayerRank {
+        rank,
+        player_uuid: r.player_uuid,
+        player_name: r.player_name,
+        value: r.value,
+    }))
+}
+
+async fn fetch_sellers(pool: &MySqlPool, since: Option<DateTime<Utc>>) -> Result<Vec<PlayerRank>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT t.player_uuid, u.player_name, CAST(SUM(t.total_amount) AS SIGNED) as "value!: i64"
+         FROM tb_market_transactions t
+         JOIN tb_user u ON u.player_uuid = t.player_uuid AND u.exclude_from_leaderboards = 0
+         WHERE t.transaction_type = 'SELL' AND (? IS NULL OR t.timestamp >= ?)
+         GROUP BY t.player_uuid, u.player_name
+         ORDER BY 3 DESC, t.player_uuid
+         LIMIT ?"#,
+        since,
+        since,
+        MAX_ENTRIES
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ranked(rows, |rank, r| PlayerRank {
+        rank,
+        player_uuid: r.player_uuid,
+        player_name: r.player_name,
+        value: r.value,
+    }))
+}
+
+async fn fetch_items(pool: &MySqlPool, since: Option<DateTime<Utc>>) -> Result<Vec<ItemRank>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT t.item_key, i.item_name,
+            CAST(SUM(t.quantity) AS SIGNED) as "quantity!: i64",
+            CAST(SUM(t.total_amount) AS SIGNED) as "gross!: i64"
+         FROM tb_market_transactions t
+         JOIN tb_market_items i ON i.item_key = t.item_key
+         JOIN tb_user u ON u.player_uuid = t.player_uuid AND u.exclude_from_leaderboards = 0
+         WHERE (? IS NULL OR t.timestamp >= ?)
+         GROUP BY t.item_key, i.item_name
+         ORDER BY 3 DESC, t.item_key
+         LIMIT ?"#,
+        since,
+        since,
+        MAX_ENTRIES
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| ItemRank {
+            rank: i + 1,
+            item_key: r.item_key,
+            item_name: r.item_name,
+            quantity: r.quantity,
+            gross: r.gross,
+        })
+        .collect())
+}
+
+fn paginate<T: Clone>(
+    board: String,
+    generated_at: DateTime<Utc>,
+    entries: &[T],
+    query: &LeaderboardQuery,
+) -> Result<LeaderboardPage<T>, ApiError> {
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if page == 0 {
+        return Err(ApiError::validation("Page starts at 1"));
+    }
+    if !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+        return Err(ApiError::validation(format!("Page size must be between 1 and {}", MAX_PAGE_SIZE)));
+    }
+
+    let start = (page - 1).saturating_mul(page_size).min(entries.len());
+    let end = start.saturating_add(page_size).min(entries.len());
+
+    Ok(LeaderboardPage {
+        board,
+        generated_at,
+        total_entries: entries.len(),
+        page,
+        page_size,
+        entries: entries[start..end].to_vec(),
+    })
+}
+
+fn cache_ttl(state: &AppState) -> Duration {
+    Duration::from_secs(state.config.current().leaderboard_cache_secs)
+}
+
+// GET /api/leaderboards/wealth - Richest players by wallet, bank or both
+pub async fn get_wealth_leaderboard(
+    Query(query): Query<LeaderboardQuery>,
+    State(pool): State<AppState>,
+) -> Result<Json<LeaderboardPage<PlayerRank>>, ApiError> {
+    let (generated_at, entries) = pool
+        .leaderboards
+        .players
+        .get_or_load(PlayerBoard::Wealth(query.by), cache_ttl(&pool), |_| fetch_wealth(&pool.pool, query.by))
+        .await?;
+
+    let board = format!("wealth:{}", query.by.as_str());
+    Ok(Json(paginate(board, generated_at, &entries, &query)?))
+}
+
+// GET /api/leaderboards/sellers - Players with the highest gross market sales
+pub async fn get_sellers_leaderboard(
+    Query(query): Query<LeaderboardQuery>,
+    State(pool): State<AppState>,
+) -> Result<Json<LeaderboardPage<PlayerRank>>, ApiError> {
+    let (generated_at, entries) = pool
+        .leaderboards
+        .players
+        .get_or_load(PlayerBoard::Sellers(query.window), cache_ttl(&pool), |now| {
+            fetch_sellers(&pool.pool, query.window.since(now))
+        })
+        .await?;
+
+    let board = format!("sellers:{}", query.window.as_str());
+    Ok(Json(paginate(board, generated_at, &entries, &query)?))
+}
+
+// GET /api/leaderboards/items - Most traded items by quantity
+pub async fn get_items_leaderboard(
+    Query(query): Query<LeaderboardQuery>,
+    State(pool): State<AppState>,
+) -> Result<Json<LeaderboardPage<ItemRank>>, ApiError> {
+    let (generated_at, entries) = pool
+        .leaderboards
+        .items
+        .get_or_load(query.window, cache_ttl(&pool), |now| fetch_items(&pool.pool, query.window.since(now)))
+        .await?;
+
+    let board = format!("items:{}", query.window.as_str());
+    Ok(Json(paginate(board, generated_at, &entries, &query)?))
+}
+
+// GET /api/admin/leaderboards/exclusions - Players hidden from every leaderboard
+pub async fn list_leaderboard_exclusions(
+    State(pool): State<AppState>,
+) -> Result<Json<Vec<ExcludedPlayer>>, ApiError> {
+    let players = sqlx::query_as!(
+        ExcludedPlayer,
+        "SELECT player_uuid, player_name FROM tb_user WHERE exclude_from_leaderboards = 1 ORDER BY player_name"
+    )
+    .fetch_all(&pool.pool)
+    .await?;
+
+    Ok(Json(players))
+}
+
+async fn set_excluded(state: &AppState, uuid: &str, excluded: bool) -> Result<(), ApiError> {
+    validate_player_uuid(uuid)?;
+    if get_user_by_uuid(&state.pool, uuid).await?.is_none() {
+        return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+    }
+
+    sqlx::query!(
+        "UPDATE tb_user SET exclude_from_leaderboards = ? WHERE player_uuid = ?",
+        excluded,
+        uuid
+    )
+    .execute(&state.pool)
+    .await?;
+
+    // Otherwise the change wouldn't show until the boards expire
+    state.leaderboards.clear();
+    Ok(())
+}
+
+// PUT /api/admin/leaderboards/exclusions/{uuid} - Hide an admin or system account
+pub async fn exclude_from_leaderboards(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    set_excluded(&pool, &uuid, true).await?;
+
+    tracing::info!("🏆 {} excluded {} from leaderboards", client.name, uuid);
+    Ok(Json(serde_json::json!({ "success": true, "player_uuid": uuid, "excluded": true })))
+}
+
+// DELETE /api/admin/leaderboards/exclusions/{uuid} - Show a player on leaderboards again
+pub async fn include_in_leaderboards(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    set_excluded(&pool, &uuid, false).await?;
+
+    tracing::info!("🏆 {} returned {} to leaderboards", client.name, uuid);
+    Ok(Json(serde_json::json!({ "success": true, "player_uuid": uuid, "excluded": false })))
+}
@@ -0,0 +1,321 @@
+// api/ledger.rs
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::{
+    api::{
+        error::ApiError,
+        validation::{validate_page_limit, validate_player_uuid},
+    },
+    AppState,
+};
+
+/// Account that a ledger entry posts against.
+#[derive(Debug, Clone, Copy)]
+pub enum Account<'a> {
+    Wallet(&'a str),
+    Bank(&'a str),
+    System(SystemAccount),
+}
+
+/// Sink/source accounts owned by the server rather than a player.
+#[derive(Debug, Clone, Copy)]
+pub enum SystemAccount {
+    Market,
+    MarketFees,
+    Vat,
+    TransferFees,
+    P2pFees,
+    Opening,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Reason {
+    MarketSell,
+    MarketBuy,
+    Transfer,
+    P2pPayment,
+    OpeningBalance,
+}
+
+impl Account<'_> {
+    fn parts(&self) -> (&'static str, &str) {
+        match self {
+            Account::Wallet(uuid) => ("WALLET", uuid),
+            Account::Bank(uuid) => ("BANK", uuid),
+            Account::System(system) => ("SYSTEM", system.as_str()),
+        }
+    }
+}
+
+impl SystemAccount {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemAccount::Market => "market",
+            SystemAccount::MarketFees => "market_fees",
+            SystemAccount::Vat => "vat",
+            SystemAccount::TransferFees => "transfer_fees",
+            SystemAccount::P2pFees => "p2p_fees",
+            SystemAccount::Opening => "opening",
+        }
+    }
+}
+
+impl Reason {
+    pub const ALL: [Reason; 5] = [
+        Reason::MarketSell,
+        Reason::MarketBuy,
+        Reason::Transfer,
+        Reason::P2pPayment,
+        Reason::OpeningBalance,
+    ];
+
+    /// Accepts the stored name in any case, e.g. `market_sell`.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|reason| reason.as_str().eq_ignore_ascii_case(name))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::MarketSell => "MARKET_SELL",
+            Reason::MarketBuy => "MARKET_BUY",
+            Reason::Transfer => "TRANSFER",
+            Reason::P2pPayment => "P2P_PAYMENT",
+            Reason::OpeningBalance => "OPENING_BALANCE",
+        }
+    }
+}
+
+/// A balanced set of ledger entries. Every credit must be matched by debits
+/// of the same total before the journal can be posted.
+#[derive(Debug)]
+pub struct Journal<'a> {
+    reason: Reason,
+    reference: Option<String>,
+    entries: Vec<(Account<'a>, i64)>,
+}
+
+impl<'a> Journal<'a> {
+    pub fn new(reason: Reason) -> Self {
+        Self {
+            reason,
+            reference: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Links the journal to the row that caused it, e.g. a market transaction id.
+    pub fn reference(mut self, reference: impl ToString) -> Self {
+        self.reference = Some(reference.to_string());
+        self
+    }
+
+    /// Increases the balance of `account`.
+    pub fn credit(mut self, account: Account<'a>, amount: impl Into<i64>) -> Self {
+        let amount = amount.into();
+        if amount != 0 {
+            self.entries.push((account, amount));
+        }
+        self
+    }
+
+    /// Decreases the balance of `account`.
+    pub fn debit(mut self, account: Account<'a>, amount: impl Into<i64>) -> Self {
+        let amount = amount.into();
+        if amount != 0 {
+            self.entries.push((account, -amount));
+        }
+        self
+    }
+
+    /// Writes the journal and its entries using the caller's transaction.
+    pub async fn post(self, conn: &mut MySqlConnection) -> Result<u64, sqlx::Error> {
+        let total: i64 = self.entries.iter().map(|(_, amount)| amount).sum();
+        if total != 0 {
+            return Err(sqlx::Error::Protocol(format!(
+                "unbalanced {} journal (off by {})",
+                self.reason.as_str(),
+                total
+            )));
+        }
+
+        let journal_id = sqlx::query!(
+            "INSERT INTO tb_ledger_journal (reason, reference) VALUES (?, ?)",
+            self.reason.as_str(),
+            self.reference
+        )
+        .execute(&mut *conn)
+        .await?
+        .last_insert_id();
+
+        for (account, amount) in &self.entries {
+            let (account_type, account_id) = account.parts();
+            sqlx::query!(
+                "INSERT INTO tb_ledger (journal_id, account_type, account_id, amount) VALUES (?, ?, ?, ?)",
+                journal_id,
+                account_type,
+                account_id,
+                amount
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(journal_id)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerEntry {
+    pub id: i64,
+    pub journal_id: i64,
+    pub reason: String,
+    pub reference: Option<String>,
+    pub account_type: String,
+    pub amount: i64,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LedgerQuery {
+    /// `next_cursor` from the previous page: only entries older than it.
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerPage {
+    pub player_uuid: String,
+    pub entries: Vec<LedgerEntry>,
+    pub next_cursor: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerAudit {
+    pub player_uuid: String,
+    pub wallet: i64,
+    pub ledger_wallet: i64,
+    pub bank: i64,
+    pub ledger_bank: i64,
+    pub balanced: bool,
+}
+
+/// Posts an opening balance for every player that has money but no ledger
+/// history yet, so balances that predate the ledger can still be rebuilt.
+pub async fn backfill_opening_balances(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let users = sqlx::query!(
+        "SELECT player_uuid, wallet, bank FROM tb_user u
+         WHERE (wallet <> 0 OR bank <> 0)
+         AND NOT EXISTS (SELECT 1 FROM tb_ledger l WHERE l.account_id = u.player_uuid AND l.account_type IN ('WALLET', 'BANK'))"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tx = pool.begin().await?;
+    for user in &users {
+        Journal::new(Reason::OpeningBalance)
+            .credit(Account::Wallet(&user.player_uuid), user.wallet)
+            .credit(Account::Bank(&user.player_uuid), user.bank)
+            .debit(Account::System(SystemAccount::Opening), user.wallet + user.bank)
+            .post(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(users.len() as u64)
+}
+
+async fn get_ledger_entries(
+    pool: &MySqlPool,
+    uuid: &str,
+    before: Option<i64>,
+    limit: i64,
+) -> Result<Vec<LedgerEntry>, sqlx::Error> {
+    let entries = sqlx::query_as!(
+        LedgerEntry,
+        "SELECT l.id, l.journal_id, j.reason, j.reference, l.account_type, l.amount, l.created_at
+         FROM tb_ledger l JOIN tb_ledger_journal j ON j.id = l.journal_id
+         WHERE l.account_id = ? AND l.account_type IN ('WALLET', 'BANK') AND (? IS NULL OR l.id < ?)
+         ORDER BY l.id DESC LIMIT ?",
+        uuid,
+        before,
+        before,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+async fn audit_balance(pool: &MySqlPool, uuid: &str) -> Result<Option<LedgerAudit>, sqlx::Error> {
+    let Some(user) = crate::api::user::get_user_by_uuid(pool, uuid).await? else {
+        return Ok(None);
+    };
+
+    let sums = sqlx::query!(
+        r#"SELECT
+            CAST(COALESCE(SUM(CASE WHEN account_type = 'WALLET' THEN amount END), 0) AS SIGNED) as "wallet!: i64",
+            CAST(COALESCE(SUM(CASE WHEN account_type = 'BANK' THEN amount END), 0) AS SIGNED) as "bank!: i64"
+         FROM tb_ledger WHERE account_id = ? AND account_type IN ('WALLET', 'BANK')"#,
+        uuid
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Some(LedgerAudit {
+        balanced: user.wallet == sums.wallet && user.bank == sums.bank,
+        player_uuid: user.player_uuid,
+        wallet: user.wallet,
+        ledger_wallet: sums.wallet,
+        bank: user.bank,
+        ledger_bank: sums.bank,
+    }))
+}
+
+// GET /api/user/{uuid}/ledger - Ledger entries for a player, newest first
+pub async fn get_user_ledger(
+    Path(uuid): Path<String>,
+    Query(query): Query<LedgerQuery>,
+    State(pool): State<AppState>,
+) -> Result<Json<LedgerPage>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    let limit = validate_page_limit(query.limit)?;
+
+    // One extra row tells us whether there is another page
+    let mut entries = get_ledger_entries(&pool.pool, &uuid, query.before, limit + 1).await?;
+    let next_cursor = if entries.len() as i64 > limit {
+        entries.truncate(limit as usize);
+        entries.last().map(|entry| entry.id)
+    } else {
+        None
+    };
+
+    Ok(Json(LedgerPage {
+        player_uuid: uuid,
+        entries,
+        next_cursor,
+    }))
+}
+
+// GET /api/user/{uuid}/ledger/audit - Rebuild balances from the ledger and compare
+pub async fn get_user_ledger_audit(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<LedgerAudit>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    match audit_balance(&pool.pool, &uuid).await? {
+        Some(audit) => {
+            if !audit.balanced {
+                tracing::warn!("⚠️ Ledger mismatch for {}: {:?}", uuid, audit);
+            }
+            Ok(Json(audit))
+        }
+        None => Err(ApiError::not_found(format!("No user found with UUID {}", uuid))),
+    }
+}
@@ -1,18 +1,42 @@
 // api/market.rs
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
     response::Json,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
-
-use crate::{api::ConfigManager, AppState};
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    api::{
+        config::MarketFees,
+        error::{ApiError, ErrorBody},
+        ledger::{Account, Journal, Reason, SystemAccount},
+        price_history::{record_price, PriceSource},
+        pricing::load_pricing,
+        quotes::{check_price_limit, PriceProtection},
+        user::lock_user,
+        validation::{trade_total, validate_item_key, validate_player_uuid, validate_quantity},
+        ConfigManager,
+    },
+    fees::{FeeOperation, FeeSchedule},
+    money::{Money, Rate},
+    pricing::{MarketState, Side},
+    AppState,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct SellItemRequest {
     pub item_key: String,
     pub quantity: i32,
+    /// From `POST /api/market/quote`, rejects the sale if the price dropped.
+    #[serde(default)]
+    pub quote_id: Option<String>,
+    #[serde(default)]
+    pub min_price_per_unit: Option<i64>,
+    /// How far below the quoted price the sale may still go through, e.g. `0.02`.
+    #[serde(default)]
+    pub max_slippage: Option<Rate>,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,6 +53,94 @@ pub struct SellItemResponse {
     pub new_item_price: i64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BuyItemRequest {
+    pub item_key: String,
+    pub quantity: i32,
+    /// From `POST /api/market/quote`, rejects the purchase if the price rose.
+    #[serde(default)]
+    pub quote_id: Option<String>,
+    #[serde(default)]
+    pub max_price_per_unit: Option<i64>,
+    /// How far above the quoted price the purchase may still go through.
+    #[serde(default)]
+    pub max_slippage: Option<Rate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuyItemResponse {
+    pub success: bool,
+    pub message: String,
+    pub gross_cost: i64,
+    pub transaction_fee: i64,
+    pub vat: i64,
+    pub total_cost: i64,
+    pub price_per_unit: i64,
+    pub new_wallet: i64,
+    pub new_bank: i64,
+    pub new_item_price: i64,
+}
+
+/// Most item types a single batch sell may contain.
+const MAX_BATCH_LINES: usize = 100;
+
+/// What a batch sell does when a line can't be sold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// Any failing line cancels the whole batch.
+    #[default]
+    Atomic,
+    /// Failing lines are reported and skipped, the rest are sold.
+    BestEffort,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchSellLine {
+    pub item_key: String,
+    pub quantity: i32,
+    #[serde(default)]
+    pub min_price_per_unit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchSellRequest {
+    pub items: Vec<BatchSellLine>,
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchLineResult {
+    pub item_key: String,
+    pub quantity: i32,
+    pub sold: bool,
+    pub price_per_unit: Option<i64>,
+    pub gross_earned: i64,
+    pub transaction_fee: i64,
+    pub vat: i64,
+    pub net_earned: i64,
+    pub new_item_price: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchSellResponse {
+    /// False when a best-effort batch skipped at least one line.
+    pub success: bool,
+    pub message: String,
+    pub mode: BatchMode,
+    pub lines: Vec<BatchLineResult>,
+    pub gross_earned: i64,
+    pub transaction_fee: i64,
+    pub vat: i64,
+    /// Everything the batch added to the player's wallet.
+    pub wallet_delta: i64,
+    pub new_wallet: i64,
+    pub new_bank: i64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct MarketItem {
     pub id: i32,
@@ -40,6 +152,9 @@ pub struct MarketItem {
     pub total_sold: i64,
     pub total_bought: i64,
     pub price_multiplier: f64,
+    pub min_multiplier: f64,
+    pub max_multiplier: f64,
+    pub is_enabled: i8,
 }
 #[derive(Debug, Serialize)]
 pub struct LightMarketItem {
@@ -53,108 +168,290 @@ pub async fn sell_item(
     Path(uuid): Path<String>,
     State(pool): State<AppState>,
     Json(payload): Json<SellItemRequest>,
-) -> Result<Json<SellItemResponse>, StatusCode> {
-    let config = match ConfigManager::load_from_db(&pool.pool).await {
-        Ok(config) => config,
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<SellItemResponse>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    validate_item_key(&payload.item_key)?;
+    validate_quantity(payload.quantity)?;
+
+    let protection = PriceProtection {
+        quote_id: payload.quote_id.clone(),
+        limit_price_per_unit: payload.min_price_per_unit,
+        max_slippage: payload.max_slippage,
+    };
+    let price_limit = protection.limit(&pool.quotes, &uuid, Side::Sell, &payload.item_key, payload.quantity)?;
+
+    let config = pool.config.current();
+
+    let mut tx = pool.pool.begin().await?;
+
+    // Lock the item row first so concurrent sells queue up behind this price
+    let market_item = lock_market_item(&mut tx, &payload.item_key)
+        .await?
+        .ok_or_else(|| ApiError::ItemUnavailable(payload.item_key.clone()))?;
+
+    let user = match lock_user(&mut tx, &uuid).await? {
+        Some(user) => user,
+        None => {
+            tracing::warn!("Sell rejected, no user found with UUID {}", uuid);
+            return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+        }
     };
 
-    let market_item = match get_market_item(&pool.pool, &payload.item_key).await {
-        Ok(Some(item)) => item,
-        Ok(None) => {
-            return Ok(Json(SellItemResponse {
-                success: false,
-                message: "Item not available in market".to_string(),
+    let price_per_unit = market_item.current_sell_price;
+    check_price_limit(Side::Sell, price_limit, price_per_unit)?;
+    let gross_earned = trade_total(price_per_unit, payload.quantity)?;
+
+    let schedule = config.fee_schedule(FeeOperation::MarketSell);
+    let fees = config.calculate_market_fees(&schedule, gross_earned)?;
+
+    let new_price = record_sale(&mut tx, &uuid, &market_item, payload.quantity, &fees).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(SellItemResponse {
+        success: true,
+        message: format!("Successfully sold {} x{}", payload.item_key, payload.quantity),
+        gross_earned: fees.gross_amount.amount(),
+        transaction_fee: fees.transaction_fee.amount(),
+        vat: fees.vat.amount(),
+        net_earned: fees.net_amount.amount(),
+        price_per_unit,
+        new_wallet: Money::new(user.wallet).checked_add(fees.net_amount)?.amount(),
+        new_bank: user.bank,
+        new_item_price: new_price,
+    }))
+}
+
+// POST /api/market/sell/{uuid}/batch - Player sells several item types in one transaction
+pub async fn sell_items_batch(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+    Json(payload): Json<BatchSellRequest>,
+) -> Result<Json<BatchSellResponse>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    if payload.items.is_empty() || payload.items.len() > MAX_BATCH_LINES {
+        return Err(ApiError::validation(format!("A batch must have between 1 and {} items", MAX_BATCH_LINES)));
+    }
+    let mut seen = HashSet::new();
+    for line in &payload.items {
+        validate_item_key(&line.item_key)?;
+        validate_quantity(line.quantity)?;
+        if !seen.insert(line.item_key.as_str()) {
+            return Err(ApiError::validation(format!("{} appears more than once in the batch", line.item_key)));
+        }
+        if line.min_price_per_unit.is_some_and(|limit| limit <= 0) {
+            return Err(ApiError::validation("Price limit must be greater than 0"));
+        }
+    }
+
+    let config = pool.config.current();
+
+    let mut tx = pool.pool.begin().await?;
+
+    // Items before the user, in key order, so batches and single sells can't deadlock
+    let mut keys: Vec<&str> = seen.into_iter().collect();
+    keys.sort_unstable();
+    let mut items = HashMap::new();
+    for key in keys {
+        if let Some(item) = lock_market_item(&mut tx, key).await? {
+            items.insert(key, item);
+        }
+    }
+
+    let user = match lock_user(&mut tx, &uuid).await? {
+        Some(user) => user,
+        None => {
+            tracing::warn!("Batch sell rejected, no user found with UUID {}", uuid);
+            return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+        }
+    };
+
+    let schedule = config.fee_schedule(FeeOperation::MarketSell);
+
+    let mut lines = Vec::with_capacity(payload.items.len());
+    let (mut gross, mut fee, mut vat, mut net) = (Money::ZERO, Money::ZERO, Money::ZERO, Money::ZERO);
+    for line in &payload.items {
+        let item = items.get(line.item_key.as_str());
+        let outcome = sell_line(&mut tx, &config, &schedule, &uuid, item, line).await;
+
+        let result = match outcome {
+            Ok((fees, new_price)) => {
+                gross = gross.checked_add(fees.gross_amount)?;
+                fee = fee.checked_add(fees.transaction_fee)?;
+                vat = vat.checked_add(fees.vat)?;
+                net = net.checked_add(fees.net_amount)?;
+                BatchLineResult {
+                    item_key: line.item_key.clone(),
+                    quantity: line.quantity,
+                    sold: true,
+                    price_per_unit: item.map(|item| item.current_sell_price),
+                    gross_earned: fees.gross_amount.amount(),
+                    transaction_fee: fees.transaction_fee.amount(),
+                    vat: fees.vat.amount(),
+                    net_earned: fees.net_amount.amount(),
+                    new_item_price: Some(new_price),
+                    error: None,
+                }
+            }
+            // A database error may have left the line half written, so it always fails the batch
+            Err(e @ ApiError::Database(_)) => return Err(e),
+            Err(e) if payload.mode == BatchMode::Atomic => {
+                tracing::warn!("Batch sell for {} rejected at {}", uuid, line.item_key);
+                return Err(e);
+            }
+            Err(e) => BatchLineResult {
+                item_key: line.item_key.clone(),
+                quantity: line.quantity,
+                sold: false,
+                price_per_unit: item.map(|item| item.current_sell_price),
                 gross_earned: 0,
                 transaction_fee: 0,
                 vat: 0,
                 net_earned: 0,
-                price_per_unit: 0,
-                new_wallet: 0,
-                new_bank: 0,
-                new_item_price: 0,
-            }));
-        }
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+                new_item_price: None,
+                error: Some(e.body()),
+            },
+        };
+        lines.push(result);
+    }
+
+    tx.commit().await?;
+
+    let sold = lines.iter().filter(|line| line.sold).count();
+    Ok(Json(BatchSellResponse {
+        success: sold == lines.len(),
+        message: format!("Sold {} of {} item types", sold, lines.len()),
+        mode: payload.mode,
+        lines,
+        gross_earned: gross.amount(),
+        transaction_fee: fee.amount(),
+        vat: vat.amount(),
+        wallet_delta: net.amount(),
+        new_wallet: Money::new(user.wallet).checked_add(net)?.amount(),
+        new_bank: user.bank,
+    }))
+}
+
+/// One line of a batch. Every check runs before anything is written, so a
+/// rejected line leaves nothing behind.
+async fn sell_line(
+    conn: &mut MySqlConnection,
+    config: &ConfigManager,
+    schedule: &FeeSchedule,
+    uuid: &str,
+    item: Option<&MarketItem>,
+    line: &BatchSellLine,
+) -> Result<(MarketFees, i64), ApiError> {
+    let item = item.ok_or_else(|| ApiError::ItemUnavailable(line.item_key.clone()))?;
+    check_price_limit(Side::Sell, line.min_price_per_unit, item.current_sell_price)?;
+    let gross_earned = trade_total(item.current_sell_price, line.quantity)?;
+    let fees = config.calculate_market_fees(schedule, gross_earned)?;
+
+    let new_price = record_sale(conn, uuid, item, line.quantity, &fees).await?;
+    Ok((fees, new_price))
+}
+
+// POST /api/market/buy/{uuid} - Player buys items
+pub async fn buy_item(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+    Json(payload): Json<BuyItemRequest>,
+) -> Result<Json<BuyItemResponse>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    validate_item_key(&payload.item_key)?;
+    validate_quantity(payload.quantity)?;
+
+    let protection = PriceProtection {
+        quote_id: payload.quote_id.clone(),
+        limit_price_per_unit: payload.max_price_per_unit,
+        max_slippage: payload.max_slippage,
     };
+    let price_limit = protection.limit(&pool.quotes, &uuid, Side::Buy, &payload.item_key, payload.quantity)?;
 
-    let price_per_unit = market_item.current_sell_price;
-    let gross_earned = price_per_unit * payload.quantity as i64;
-    
-    // Calculate fees using config
-    let fees = config.calculate_market_fees(gross_earned);
-
-    let mut tx = match pool.pool.begin().await {
-        Ok(tx) => tx,
-        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    let config = pool.config.current();
+
+    let mut tx = pool.pool.begin().await?;
+
+    let market_item = lock_market_item(&mut tx, &payload.item_key)
+        .await?
+        .ok_or_else(|| ApiError::ItemUnavailable(payload.item_key.clone()))?;
+
+    let user = match lock_user(&mut tx, &uuid).await? {
+        Some(user) => user,
+        None => {
+            tracing::warn!("Buy rejected, no user found with UUID {}", uuid);
+            return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+        }
     };
 
-    // Update player wallet only (no bank option)
-    let update_result = sqlx::query!(
-        "UPDATE tb_user SET wallet = wallet + ? WHERE player_uuid = ?",
-        fees.net_amount,
+    let price_per_unit = market_item.current_buy_price;
+    check_price_limit(Side::Buy, price_limit, price_per_unit)?;
+    let gross_cost = trade_total(price_per_unit, payload.quantity)?;
+
+    // Buyer pays the fees on top of the item price
+    let schedule = config.fee_schedule(FeeOperation::MarketBuy);
+    let fees = config.calculate_market_buy_fees(&schedule, gross_cost)?;
+
+    if user.wallet < fees.net_amount.amount() {
+        return Err(ApiError::InsufficientFunds {
+            source: "wallet".to_string(),
+            have: user.wallet,
+            need: fees.net_amount.amount(),
+        });
+    }
+
+    sqlx::query!(
+        "UPDATE tb_user SET wallet = wallet - ? WHERE player_uuid = ?",
+        fees.net_amount.amount(),
         uuid
     )
     .execute(&mut *tx)
-    .await;
-
-    if update_result.is_err() {
-        let _ = tx.rollback().await;
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+    .await?;
 
     // Record transaction
-    let transaction_result = sqlx::query!(
-        "INSERT INTO tb_market_transactions (player_uuid, item_key, transaction_type, quantity, price_per_unit, total_amount, price_multiplier) VALUES (?, ?, 'SELL', ?, ?, ?, ?)",
+    let transaction_id = sqlx::query!(
+        "INSERT INTO tb_market_transactions (player_uuid, item_key, transaction_type, quantity, price_per_unit, total_amount, price_multiplier) VALUES (?, ?, 'BUY', ?, ?, ?, ?)",
         uuid,
         payload.item_key,
         payload.quantity,
         price_per_unit,
-        fees.gross_amount,
+        fees.gross_amount.amount(),
         market_item.price_multiplier
     )
     .execute(&mut *tx)
-    .await;
-
-    if transaction_result.is_err() {
-        let _ = tx.rollback().await;
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+    .await?
+    .last_insert_id();
+
+    Journal::new(Reason::MarketBuy)
+        .reference(transaction_id)
+        .debit(Account::Wallet(&uuid), fees.net_amount)
+        .credit(Account::System(SystemAccount::Market), fees.gross_amount)
+        .credit(Account::System(SystemAccount::MarketFees), fees.transaction_fee)
+        .credit(Account::System(SystemAccount::Vat), fees.vat)
+        .post(&mut *tx)
+        .await?;
 
-    let market_update_result = sqlx::query!(
-        "UPDATE tb_market_items SET total_sold = total_sold + ? WHERE item_key = ?",
+    sqlx::query!(
+        "UPDATE tb_market_items SET total_bought = total_bought + ? WHERE item_key = ?",
         payload.quantity,
         payload.item_key
     )
     .execute(&mut *tx)
-    .await;
-
-    if market_update_result.is_err() {
-        let _ = tx.rollback().await;
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
-
-    if tx.commit().await.is_err() {
-        return Err(StatusCode::INTERNAL_SERVER_ERROR);
-    }
+    .await?;
 
-    let new_price = update_market_price(&pool.pool, &payload.item_key, "SELL", payload.quantity).await
-        .unwrap_or(price_per_unit);
+    let (_, new_price) = update_market_price(&mut tx, &market_item, Side::Buy, payload.quantity).await?;
 
-    let user = match crate::api::user::get_user_by_uuid(&pool.pool, &uuid).await {
-        Ok(Some(user)) => user,
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-    };
+    tx.commit().await?;
 
-    Ok(Json(SellItemResponse {
+    Ok(Json(BuyItemResponse {
         success: true,
-        message: format!("Successfully sold {} x{}", payload.item_key, payload.quantity),
-        gross_earned: fees.gross_amount,
-        transaction_fee: fees.transaction_fee,
-        vat: fees.vat,
-        net_earned: fees.net_amount,
+        message: format!("Successfully bought {} x{}", payload.item_key, payload.quantity),
+        gross_cost: fees.gross_amount.amount(),
+        transaction_fee: fees.transaction_fee.amount(),
+        vat: fees.vat.amount(),
+        total_cost: fees.net_amount.amount(),
         price_per_unit,
-        new_wallet: user.wallet,
+        new_wallet: user.wallet - fees.net_amount.amount(),
         new_bank: user.bank,
         new_item_price: new_price,
     }))
@@ -163,38 +460,33 @@ pub async fn sell_item(
 // GET /api/market/items - Get all market items
 pub async fn get_market_items(
     State(pool): State<AppState>,
-) -> Result<Json<Vec<MarketItem>>, StatusCode> {
-    match get_all_market_items(&pool.pool).await {
-        Ok(items) => Ok(Json(items)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+) -> Result<Json<Vec<MarketItem>>, ApiError> {
+    Ok(Json(get_all_market_items(&pool.pool).await?))
 }
 
 // GET /api/market/item/{key} - Get specific market item
 pub async fn get_market_item_endpoint(
     Path(item_key): Path<String>,
     State(pool): State<AppState>,
-) -> Result<Json<MarketItem>, StatusCode> {
-    match get_market_item(&pool.pool, &item_key).await {
-        Ok(Some(item)) => Ok(Json(item)),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+) -> Result<Json<MarketItem>, ApiError> {
+    validate_item_key(&item_key)?;
+
+    match get_market_item(&pool.pool, &item_key).await? {
+        Some(item) => Ok(Json(item)),
+        None => Err(ApiError::ItemUnavailable(item_key)),
     }
 }
 // GET /api/market/item/light - Get specific market item price
 pub async fn get_market_items_light(
     State(app_state): State<AppState>,
-) -> Result<Json<Vec<LightMarketItem>>, StatusCode> {
-    match get_all_market_items_light(&app_state.pool).await {
-        Ok(items) => Ok(Json(items)),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
-    }
+) -> Result<Json<Vec<LightMarketItem>>, ApiError> {
+    Ok(Json(get_all_market_items_light(&app_state.pool).await?))
 }
 
-async fn get_market_item(pool: &MySqlPool, item_key: &str) -> Result<Option<MarketItem>, sqlx::Error> {
+pub async fn get_market_item(pool: &MySqlPool, item_key: &str) -> Result<Option<MarketItem>, sqlx::Error> {
     let item = sqlx::query_as!(
         MarketItem,
-        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier FROM tb_market_items WHERE item_key = ?",
+        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier, min_multiplier, max_multiplier, is_enabled FROM tb_market_items WHERE item_key = ? AND is_enabled = 1",
         item_key
     )
     .fetch_optional(pool)
@@ -202,10 +494,22 @@ async fn get_market_item(pool: &MySqlPool, item_key: &str) -> Result<Option<Mark
 
     Ok(item)
 }
+async fn lock_market_item(conn: &mut MySqlConnection, item_key: &str) -> Result<Option<MarketItem>, sqlx::Error> {
+    let item = sqlx::query_as!(
+        MarketItem,
+        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier, min_multiplier, max_multiplier, is_enabled FROM tb_market_items WHERE item_key = ? AND is_enabled = 1 FOR UPDATE",
+        item_key
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(item)
+}
+
 async fn get_all_market_items_light(pool: &MySqlPool) -> Result<Vec<LightMarketItem>, sqlx::Error> {
     let items = sqlx::query_as!(
         LightMarketItem,
-        "SELECT item_key, current_sell_price, price_multiplier FROM tb_market_items ORDER BY item_key"
+        "SELECT item_key, current_sell_price, price_multiplier FROM tb_market_items WHERE is_enabled = 1 ORDER BY item_key"
     )
     .fetch_all(pool)
     .await?;
@@ -216,7 +520,7 @@ async fn get_all_market_items_light(pool: &MySqlPool) -> Result<Vec<LightMarketI
 async fn get_all_market_items(pool: &MySqlPool) -> Result<Vec<MarketItem>, sqlx::Error> {
     let items = sqlx::query_as!(
         MarketItem,
-        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier FROM tb_market_items ORDER BY item_name"
+        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier, min_multiplier, max_multiplier, is_enabled FROM tb_market_items WHERE is_enabled = 1 ORDER BY item_name"
     )
     .fetch_all(pool)
     .await?;
@@ -224,13 +528,72 @@ async fn get_all_market_items(pool: &MySqlPool) -> Result<Vec<MarketItem>, sqlx:
     Ok(items)
 }
 
-async fn update_market_price(pool: &MySqlPool, item_key: &str, transaction_type: &str, quantity: i32) -> Result<i64, sqlx::Error> {
+/// Books a sale at the item's current sell price: pays the seller, records
+/// the transaction and ledger entries and reprices the item. Returns the new
+/// sell price.
+async fn record_sale(
+    conn: &mut MySqlConnection,
+    uuid: &str,
+    item: &MarketItem,
+    quantity: i32,
+    fees: &MarketFees,
+) -> Result<i64, sqlx::Error> {
+    // Update player wallet only (no bank option)
+    sqlx::query!(
+        "UPDATE tb_user SET wallet = wallet + ? WHERE player_uuid = ?",
+        fees.net_amount.amount(),
+        uuid
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // Record transaction
+    let transaction_id = sqlx::query!(
+        "INSERT INTO tb_market_transactions (player_uuid, item_key, transaction_type, quantity, price_per_unit, total_amount, price_multiplier) VALUES (?, ?, 'SELL', ?, ?, ?, ?)",
+        uuid,
+        item.item_key,
+        quantity,
+        item.current_sell_price,
+        fees.gross_amount.amount(),
+        item.price_multiplier
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_id();
+
+    Journal::new(Reason::MarketSell)
+        .reference(transaction_id)
+        .debit(Account::System(SystemAccount::Market), fees.gross_amount)
+        .credit(Account::Wallet(uuid), fees.net_amount)
+        .credit(Account::System(SystemAccount::MarketFees), fees.transaction_fee)
+        .credit(Account::System(SystemAccount::Vat), fees.vat)
+        .post(&mut *conn)
+        .await?;
+
+    sqlx::query!(
+        "UPDATE tb_market_items SET total_sold = total_sold + ? WHERE item_key = ?",
+        quantity,
+        item.item_key
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let (new_price, _) = update_market_price(conn, item, Side::Sell, quantity).await?;
+    Ok(new_price)
+}
+
+/// Reprices an item after a trade using its pricing model and returns the new (sell, buy) prices.
+/// Runs inside the trade's transaction, so the item row is already locked and
+/// the recent-volume sums include the trade being made.
+async fn update_market_price(conn: &mut MySqlConnection, item: &MarketItem, side: Side, quantity: i32) -> Result<(i64, i64), sqlx::Error> {
+    let item_key = item.item_key.as_str();
+
     let recent_sales = sqlx::query!(
         "SELECT CAST(COALESCE(SUM(quantity), 0) AS SIGNED) as total_sold FROM tb_market_transactions 
          WHERE item_key = ? AND transaction_type = 'SELL' AND timestamp >= DATE_SUB(NOW(), INTERVAL 1 HOUR)",
         item_key
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
     let recent_buys = sqlx::query!(
@@ -238,58 +601,38 @@ async fn update_market_price(pool: &MySqlPool, item_key: &str, transaction_type:
          WHERE item_key = ? AND transaction_type = 'BUY' AND timestamp >= DATE_SUB(NOW(), INTERVAL 1 HOUR)",
         item_key
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
-    let sales_volume = recent_sales.total_sold as f64;
-    let buy_volume = recent_buys.total_bought as f64;
-
-    let item = get_market_item(pool, item_key).await?.unwrap();
-    let base_price = item.base_price as f64;
-    let mut current_multiplier = item.price_multiplier;
-
-    let supply_demand_ratio = if buy_volume > 0.0 {
-        sales_volume / buy_volume
-    } else if sales_volume > 0.0 {
-        2.0
-    } else {
-        1.0
-    };
-
-    let price_change = match transaction_type {
-        "SELL" => {
-            let volume_factor = (quantity as f64) / 64.0;
-            -0.02 * volume_factor * (1.0 + supply_demand_ratio * 0.5)
-        }
-        "BUY" => {
-            let volume_factor = (quantity as f64) / 64.0;
-            0.02 * volume_factor * (1.0 + (1.0 / supply_demand_ratio.max(0.1)) * 0.5)
-        }
-        _ => 0.0,
+    let pricing = load_pricing(&mut *conn, item_key).await?;
+    let state = MarketState {
+        base_price: item.base_price,
+        multiplier: item.price_multiplier,
+        min_multiplier: item.min_multiplier,
+        max_multiplier: item.max_multiplier,
+        recent_sold: recent_sales.total_sold,
+        recent_bought: recent_buys.total_bought,
     };
 
-    current_multiplier += price_change;
-    let baseline_pull = (1.0 - current_multiplier) * 0.001;
-    current_multiplier += baseline_pull;
-    current_multiplier = current_multiplier.max(0.1).min(4.0);
-
-    let new_sell_price = (base_price * current_multiplier) as i64;
-    let new_buy_price = (base_price * current_multiplier * 1.6) as i64;
+    let new_multiplier = pricing.after_trade(&state, side, quantity);
+    let (new_sell_price, new_buy_price) = pricing.prices(item.base_price, new_multiplier);
 
     sqlx::query!(
         "UPDATE tb_market_items SET current_sell_price = ?, current_buy_price = ?, price_multiplier = ? WHERE item_key = ?",
         new_sell_price,
         new_buy_price,
-        current_multiplier,
+        new_multiplier,
         item_key
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
+    record_price(conn, item_key, new_multiplier, new_sell_price, new_buy_price, PriceSource::Trade).await?;
+
     tracing::info!(
         "Updated price for {}: multiplier {:.4} -> sell: {}, buy: {}",
-        item_key, current_multiplier, new_sell_price, new_buy_price
+        item_key, new_multiplier, new_sell_price, new_buy_price
     );
 
-    Ok(new_sell_price)
+    Ok((new_sell_price, new_buy_price))
 }
\ No newline at end of file
@@ -0,0 +1,327 @@
+// api/market_admin.rs
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::{
+    api::{
+        error::ApiError,
+        market::MarketItem,
+        price_history::{record_price, PriceSource},
+        pricing::load_pricing,
+        validation::validate_item_key,
+    },
+    AppState,
+};
+
+fn default_min_multiplier() -> f64 {
+    0.1
+}
+
+fn default_max_multiplier() -> f64 {
+    4.0
+}
+
+/// One catalogue row, as sent to the create and import endpoints.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemDefinition {
+    pub item_key: String,
+    pub item_name: String,
+    pub base_price: i64,
+    #[serde(default = "default_min_multiplier")]
+    pub min_multiplier: f64,
+    #[serde(default = "default_max_multiplier")]
+    pub max_multiplier: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateItemRequest {
+    pub item_name: Option<String>,
+    pub base_price: Option<i64>,
+    pub min_multiplier: Option<f64>,
+    pub max_multiplier: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub success: bool,
+    pub created: u64,
+    pub updated: u64,
+}
+
+fn validate_definition(item: &ItemDefinition) -> Result<(), ApiError> {
+    validate_item_key(&item.item_key)?;
+    if item.item_name.is_empty() || item.item_name.len() > 100 {
+        return Err(ApiError::validation(format!("{}: item name must be between 1 and 100 characters", item.item_key)));
+    }
+    if item.base_price <= 0 {
+        return Err(ApiError::validation(format!("{}: base price must be greater than 0", item.item_key)));
+    }
+    if !(item.min_multiplier > 0.0 && item.min_multiplier < item.max_multiplier && item.max_multiplier <= 100.0) {
+        return Err(ApiError::validation(format!(
+            "{}: multiplier bounds must satisfy 0 < min < max <= 100",
+            item.item_key
+        )));
+    }
+    Ok(())
+}
+
+/// Creates the item or rewrites its definition, keeping the current
+/// multiplier (clamped into the new bounds). Returns true if it was created.
+async fn upsert_item(conn: &mut MySqlConnection, item: &ItemDefinition) -> Result<bool, sqlx::Error> {
+    let existing = sqlx::query!(
+        "SELECT price_multiplier FROM tb_market_items WHERE item_key = ? FOR UPDATE",
+        item.item_key
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let created = existing.is_none();
+    let multiplier = existing
+        .map(|r| r.price_multiplier)
+        .unwrap_or(1.0)
+        .clamp(item.min_multiplier, item.max_multiplier);
+    let (sell_price, buy_price) = load_pricing(&mut *conn, &item.item_key)
+        .await?
+        .prices(item.base_price, multiplier);
+
+    if created {
+        sqlx::query!(
+            "INSERT INTO tb_market_items (item_key, item_name, base_price, current_sell_price, current_buy_price, price_multiplier, min_multiplier, max_multiplier) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            item.item_key,
+            item.item_name,
+            item.base_price,
+            sell_price,
+            buy_price,
+            multiplier,
+            item.min_multiplier,
+            item.max_multiplier
+        )
+        .execute(&mut *conn)
+        .await?;
+    } else {
+        sqlx::query!(
+            "UPDATE tb_market_items SET item_name = ?, base_price = ?, current_sell_price = ?, current_buy_price = ?, price_multiplier = ?, min_multiplier = ?, max_multiplier = ? WHERE item_key = ?",
+            item.item_name,
+            item.base_price,
+            sell_price,
+            buy_price,
+            multiplier,
+            item.min_multiplier,
+            item.max_multiplier,
+            item.item_key
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    record_price(conn, &item.item_key, multiplier, sell_price, buy_price, PriceSource::Admin).await?;
+
+    Ok(created)
+}
+
+async fn get_any_market_item(pool: &MySqlPool, item_key: &str) -> Result<Option<MarketItem>, sqlx::Error> {
+    let item = sqlx::query_as!(
+        MarketItem,
+        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier, min_multiplier, max_multiplier, is_enabled FROM tb_market_items WHERE item_key = ?",
+        item_key
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(item)
+}
+
+async fn set_item_enabled(pool: &MySqlPool, item_key: &str, enabled: bool) -> Result<MarketItem, ApiError> {
+    let result = sqlx::query!(
+        "UPDATE tb_market_items SET is_enabled = ? WHERE item_key = ?",
+        enabled,
+        item_key
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 && get_any_market_item(pool, item_key).await?.is_none() {
+        return Err(ApiError::ItemUnavailable(item_key.to_string()));
+    }
+
+    tracing::info!("🛒 Market item {} {}", item_key, if enabled { "enabled" } else { "disabled" });
+    get_any_market_item(pool, item_key)
+        .await?
+        .ok_or_else(|| ApiError::ItemUnavailable(item_key.to_string()))
+}
+
+fn parse_catalogue(headers: &HeaderMap, body: &Bytes) -> Result<Vec<ItemDefinition>, ApiError> {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/csv"));
+
+    if is_csv {
+        csv::Reader::from_reader(body.as_ref())
+            .deserialize()
+            .enumerate()
+            .map(|(line, row)| {
+                row.map_err(|e| ApiError::validation(format!("CSV row {}: {}", line + 1, e)))
+            })
+            .collect()
+    } else {
+        serde_json::from_slice(body).map_err(|e| ApiError::validation(format!("Invalid catalogue JSON: {}", e)))
+    }
+}
+
+// GET /api/admin/market/items - All market items, including disabled ones
+pub async fn list_catalogue(State(pool): State<AppState>) -> Result<Json<Vec<MarketItem>>, ApiError> {
+    let items = sqlx::query_as!(
+        MarketItem,
+        "SELECT id, item_key, item_name, base_price, current_sell_price, current_buy_price, total_sold, total_bought, price_multiplier, min_multiplier, max_multiplier, is_enabled FROM tb_market_items ORDER BY item_key"
+    )
+    .fetch_all(&pool.pool)
+    .await?;
+
+    Ok(Json(items))
+}
+
+// POST /api/admin/market/items - Add a new market item
+pub async fn create_market_item(
+    State(pool): State<AppState>,
+    Json(payload): Json<ItemDefinition>,
+) -> Result<Json<MarketItem>, ApiError> {
+    validate_definition(&payload)?;
+
+    if get_any_market_item(&pool.pool, &payload.item_key).await?.is_some() {
+        return Err(ApiError::Conflict(format!("Item {} already exists", payload.item_key)));
+    }
+
+    let mut tx = pool.pool.begin().await?;
+    upsert_item(&mut tx, &payload).await?;
+    tx.commit().await?;
+
+    tracing::info!("🛒 Added market item {} at base price {}", payload.item_key, payload.base_price);
+    get_any_market_item(&pool.pool, &payload.item_key)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::ItemUnavailable(payload.item_key))
+}
+
+// PUT /api/admin/market/items/{key} - Edit an item's name, base price or bounds
+pub async fn update_market_item(
+    Path(item_key): Path<String>,
+    State(pool): State<AppState>,
+    Json(payload): Json<UpdateItemRequest>,
+) -> Result<Json<MarketItem>, ApiError> {
+    let current = get_any_market_item(&pool.pool, &item_key)
+        .await?
+        .ok_or_else(|| ApiError::ItemUnavailable(item_key.clone()))?;
+
+    let definition = ItemDefinition {
+        item_key: item_key.clone(),
+        item_name: payload.item_name.unwrap_or(current.item_name),
+        base_price: payload.base_price.unwrap_or(current.base_price),
+        min_multiplier: payload.min_multiplier.unwrap_or(current.min_multiplier),
+        max_multiplier: payload.max_multiplier.unwrap_or(current.max_multiplier),
+    };
+    validate_definition(&definition)?;
+
+    let mut tx = pool.pool.begin().await?;
+    upsert_item(&mut tx, &definition).await?;
+    tx.commit().await?;
+
+    tracing::info!("🛒 Updated market item {}", item_key);
+    get_any_market_item(&pool.pool, &item_key)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::ItemUnavailable(item_key))
+}
+
+// POST /api/admin/market/items/{key}/disable - Hide an item from the market
+pub async fn disable_market_item(
+    Path(item_key): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<MarketItem>, ApiError> {
+    Ok(Json(set_item_enabled(&pool.pool, &item_key, false).await?))
+}
+
+// POST /api/admin/market/items/{key}/enable - Put a disabled item back on the market
+pub async fn enable_market_item(
+    Path(item_key): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<MarketItem>, ApiError> {
+    Ok(Json(set_item_enabled(&pool.pool, &item_key, true).await?))
+}
+
+// DELETE /api/admin/market/items/{key} - Delete an item that was never traded
+pub async fn delete_market_item(
+    Path(item_key): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let mut tx = pool.pool.begin().await?;
+
+    let trades = sqlx::query!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM tb_market_transactions WHERE item_key = ?"#,
+        item_key
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Deleting would cascade into the trade history, so traded items can only be disabled
+    if trades.count > 0 {
+        return Err(ApiError::Conflict(format!(
+            "Item {} has {} recorded trades, disable it instead",
+            item_key, trades.count
+        )));
+    }
+
+    let result = sqlx::query!("DELETE FROM tb_market_items WHERE item_key = ?", item_key)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::ItemUnavailable(item_key));
+    }
+    sqlx::query!("DELETE FROM tb_pricing_models WHERE scope = ?", item_key)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!("🛒 Deleted market item {}", item_key);
+    Ok(Json(serde_json::json!({ "success": true, "message": format!("Deleted {}", item_key) })))
+}
+
+// POST /api/admin/market/items/import - Bulk create/update from a JSON array or CSV
+pub async fn import_catalogue(
+    State(pool): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let items = parse_catalogue(&headers, &body)?;
+    if items.is_empty() {
+        return Err(ApiError::validation("Catalogue is empty"));
+    }
+    for item in &items {
+        validate_definition(item)?;
+    }
+
+    // All rows land together or not at all
+    let mut tx = pool.pool.begin().await?;
+    let mut created = 0;
+    for item in &items {
+        if upsert_item(&mut tx, item).await? {
+            created += 1;
+        }
+    }
+    tx.commit().await?;
+
+    let updated = items.len() as u64 - created;
+    tracing::info!("🛒 Imported catalogue: {} created, {} updated", created, updated);
+
+    Ok(Json(ImportResponse {
+        success: true,
+        created,
+        updated,
+    }))
+}
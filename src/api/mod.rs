@@ -1,5 +1,19 @@
 pub mod user;
 pub mod market;
+pub mod market_admin;
+pub mod auth;
+pub mod bank;
 pub mod config;
+pub mod error;
+pub mod fees;
+pub mod idempotency;
+pub mod leaderboard;
+pub mod ledger;
+pub mod price_history;
+pub mod pricing;
+pub mod quotes;
+pub mod regeneration;
+pub mod transactions;
+pub mod validation;
 
 pub use config::ConfigManager;
\ No newline at end of file
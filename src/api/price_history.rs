@@ -0,0 +1,300 @@
+// api/price_history.rs
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::{
+    api::{error::ApiError, validation::validate_item_key},
+    AppState,
+};
+
+/// Upper bound on candles per request so a tiny interval can't scan years of rows.
+const MAX_CANDLES: i64 = 1000;
+/// Longest candle accepted, one year.
+const MAX_INTERVAL_SECS: i64 = 365 * 24 * 60 * 60;
+
+/// What caused a price change.
+#[derive(Debug, Clone, Copy)]
+pub enum PriceSource {
+    Trade,
+    Regeneration,
+    Admin,
+}
+
+impl PriceSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceSource::Trade => "TRADE",
+            PriceSource::Regeneration => "REGENERATION",
+            PriceSource::Admin => "ADMIN",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub interval: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Candle {
+    pub start: DateTime<Utc>,
+    pub open: i64,
+    pub high: i64,
+    pub low: i64,
+    pub close: i64,
+    pub sell_volume: i64,
+    pub buy_volume: i64,
+    pub trades: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PriceHistoryResponse {
+    pub item_key: String,
+    pub interval_secs: i64,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub candles: Vec<Candle>,
+}
+
+struct BucketVolume {
+    sell_volume: i64,
+    buy_volume: i64,
+    trades: i64,
+}
+
+/// Snapshots an item's price after it changed. Runs on the caller's
+/// connection so it commits or rolls back with the change itself.
+pub async fn record_price(
+    conn: &mut MySqlConnection,
+    item_key: &str,
+    multiplier: f64,
+    sell_price: i64,
+    buy_price: i64,
+    source: PriceSource,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO tb_price_history (item_key, price_multiplier, sell_price, buy_price, source) VALUES (?, ?, ?, ?, ?)",
+        item_key,
+        multiplier,
+        sell_price,
+        buy_price,
+        source.as_str()
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Parses intervals like `5m`, `1h` or `1d` into seconds, up to a year.
+fn parse_interval(interval: &str) -> Result<i64, ApiError> {
+    let invalid = || ApiError::validation(format!("Invalid interval '{}', use e.g. 5m, 1h or 1d", interval));
+
+    if !interval.is_ascii() {
+        return Err(invalid());
+    }
+    let (count, unit) = interval.split_at(interval.len().saturating_sub(1));
+    let count: i64 = count.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    if count <= 0 {
+        return Err(invalid());
+    }
+
+    count
+        .checked_mul(unit_secs)
+        .filter(|secs| *secs <= MAX_INTERVAL_SECS)
+        .ok_or_else(invalid)
+}
+
+/// First and last interval-aligned bucket covering `[from, to)`. Bucket `n`
+/// starts `n * interval_secs` after the epoch.
+fn bucket_range(from: DateTime<Utc>, to: DateTime<Utc>, interval_secs: i64) -> (i64, i64) {
+    (from.timestamp().div_euclid(interval_secs), (to.timestamp() - 1).div_euclid(interval_secs))
+}
+
+async fn build_candles(
+    pool: &MySqlPool,
+    item_key: &str,
+    interval_secs: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<Candle>, sqlx::Error> {
+    // Price going into the window, so quiet buckets still have an open/close
+    let seed = sqlx::query!(
+        "SELECT sell_price FROM tb_price_history WHERE item_key = ? AND recorded_at < ? ORDER BY recorded_at DESC, id DESC LIMIT 1",
+        item_key,
+        from
+    )
+    .fetch_optional(pool)
+    .await?
+    .map(|r| r.sell_price);
+
+    let snapshots = sqlx::query!(
+        "SELECT sell_price, recorded_at FROM tb_price_history WHERE item_key = ? AND recorded_at >= ? AND recorded_at < ? ORDER BY recorded_at, id",
+        item_key,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let volumes = sqlx::query!(
+        r#"SELECT
+            CAST(FLOOR(UNIX_TIMESTAMP(timestamp) / ?) AS SIGNED) as "bucket!: i64",
+            CAST(COALESCE(SUM(CASE WHEN transaction_type = 'SELL' THEN quantity END), 0) AS SIGNED) as "sell_volume!: i64",
+            CAST(COALESCE(SUM(CASE WHEN transaction_type = 'BUY' THEN quantity END), 0) AS SIGNED) as "buy_volume!: i64",
+            COUNT(*) as "trades!: i64"
+         FROM tb_market_transactions
+         WHERE item_key = ? AND timestamp >= ? AND timestamp < ?
+         GROUP BY 1"#,
+        interval_secs,
+        item_key,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let volumes: HashMap<i64, BucketVolume> = volumes
+        .into_iter()
+        .map(|v| {
+            (v.bucket, BucketVolume { sell_volume: v.sell_volume, buy_volume: v.buy_volume, trades: v.trades })
+        })
+        .collect();
+
+    let (first_bucket, last_bucket) = bucket_range(from, to, interval_secs);
+
+    let mut candles = Vec::new();
+    let mut last_close = seed;
+    let mut snapshots = snapshots.into_iter().peekable();
+
+    for bucket in first_bucket..=last_bucket {
+        let bucket_end = (bucket + 1) * interval_secs;
+        let mut prices = Vec::new();
+        while let Some(snapshot) = snapshots.next_if(|s| s.recorded_at.timestamp() < bucket_end) {
+            prices.push(snapshot.sell_price);
+        }
+
+        let Some(open) = last_close.or_else(|| prices.first().copied()) else {
+            // Nothing known about the price yet
+            continue;
+        };
+        let close = prices.last().copied().unwrap_or(open);
+        let high = prices.iter().copied().fold(open, i64::max);
+        let low = prices.iter().copied().fold(open, i64::min);
+        last_close = Some(close);
+
+        let volume = volumes.get(&bucket);
+        candles.push(Candle {
+            start: Utc.timestamp_opt(bucket * interval_secs, 0).single().unwrap_or(from),
+            open,
+            high,
+            low,
+            close,
+            sell_volume: volume.map_or(0, |v| v.sell_volume),
+            buy_volume: volume.map_or(0, |v| v.buy_volume),
+            trades: volume.map_or(0, |v| v.trades),
+        });
+    }
+
+    Ok(candles)
+}
+
+// GET /api/market/item/{key}/history - OHLC candles and volume for an item
+pub async fn get_price_history(
+    Path(item_key): Path<String>,
+    Query(query): Query<HistoryQuery>,
+    State(pool): State<AppState>,
+) -> Result<Json<PriceHistoryResponse>, ApiError> {
+    validate_item_key(&item_key)?;
+
+    let interval_secs = parse_interval(query.interval.as_deref().unwrap_or("1h"))?;
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::days(1));
+
+    if from >= to {
+        return Err(ApiError::validation("'from' must be before 'to'"));
+    }
+    // Counted on aligned buckets, a range rarely starts on a bucket boundary
+    let (first_bucket, last_bucket) = bucket_range(from, to, interval_secs);
+    if last_bucket - first_bucket + 1 > MAX_CANDLES {
+        return Err(ApiError::validation(format!(
+            "Too many candles requested, widen the interval or narrow the range (max {})",
+            MAX_CANDLES
+        )));
+    }
+
+    let candles = build_candles(&pool.pool, &item_key, interval_secs, from, to).await?;
+
+    Ok(Json(PriceHistoryResponse {
+        item_key,
+        interval_secs,
+        from,
+        to,
+        candles,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_interval_reads_each_unit() {
+        assert_eq!(parse_interval("5m").unwrap(), 300);
+        assert_eq!(parse_interval("1h").unwrap(), 3600);
+        assert_eq!(parse_interval("2d").unwrap(), 172_800);
+        assert_eq!(parse_interval("365d").unwrap(), MAX_INTERVAL_SECS);
+    }
+
+    #[test]
+    fn parse_interval_rejects_bad_input() {
+        for interval in ["", "m", "5", "5s", "5w", "1H", "0m", "-5m", "1.5h", "5 m", "5é", "366d"] {
+            assert!(parse_interval(interval).is_err(), "{interval} should be rejected");
+        }
+    }
+
+    #[test]
+    fn parse_interval_rejects_overflow() {
+        assert!(parse_interval(&format!("{}d", i64::MAX)).is_err());
+        assert!(parse_interval(&format!("{}m", i64::MAX / 60 + 1)).is_err());
+        assert!(parse_interval("99999999999999999999m").is_err());
+    }
+
+    #[test]
+    fn buckets_align_to_the_interval() {
+        // 00:30 to 01:30 touches the 00:00 and 01:00 hours
+        assert_eq!(bucket_range(at(1800), at(5400), 3600), (0, 1));
+        // An end on a boundary doesn't open a new bucket
+        assert_eq!(bucket_range(at(3600), at(7200), 3600), (1, 1));
+        assert_eq!(bucket_range(at(-1), at(1), 3600), (-1, 0));
+    }
+
+    #[test]
+    fn a_partial_trailing_bucket_counts_as_a_candle() {
+        let interval = 60;
+        let (first, last) = bucket_range(at(0), at(MAX_CANDLES * interval + 1), interval);
+        assert_eq!(last - first + 1, MAX_CANDLES + 1);
+
+        let (first, last) = bucket_range(at(0), at(MAX_CANDLES * interval), interval);
+        assert_eq!(last - first + 1, MAX_CANDLES);
+    }
+}
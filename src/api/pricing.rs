@@ -0,0 +1,143 @@
+// api/pricing.rs
+use axum::{
+    extract::{Extension, Path, State},
+    response::Json,
+};
+use serde::Serialize;
+use sqlx::MySqlConnection;
+
+use crate::{
+    api::{auth::ApiClient, error::ApiError, validation::validate_item_key},
+    pricing::{PricingConfig, PricingTable, DEFAULT_SCOPE},
+    AppState,
+};
+
+#[derive(Debug, Serialize)]
+pub struct PricingModelEntry {
+    pub scope: String,
+    pub config: serde_json::Value,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// `default` in the URL stands for the global row.
+fn scope_from_path(scope: &str) -> &str {
+    if scope == "default" { DEFAULT_SCOPE } else { scope }
+}
+
+fn parse_config(scope: &str, config: &str) -> Option<PricingConfig> {
+    match serde_json::from_str(config) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            tracing::error!("Ignoring invalid pricing model for {}: {}", scope, e);
+            None
+        }
+    }
+}
+
+/// The model for one item: its own row, else the global row, else the built-in default.
+pub async fn load_pricing(conn: &mut MySqlConnection, item_key: &str) -> Result<PricingConfig, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT scope, config FROM tb_pricing_models WHERE scope IN (?, ?) ORDER BY scope = ?",
+        item_key,
+        DEFAULT_SCOPE,
+        DEFAULT_SCOPE
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .find_map(|r| parse_config(&r.scope, &r.config))
+        .unwrap_or_default())
+}
+
+pub async fn load_pricing_table(conn: &mut MySqlConnection) -> Result<PricingTable, sqlx::Error> {
+    let rows = sqlx::query!("SELECT scope, config FROM tb_pricing_models")
+        .fetch_all(conn)
+        .await?;
+
+    let mut table = PricingTable::default();
+    for row in rows {
+        if let Some(config) = parse_config(&row.scope, &row.config) {
+            table.insert(row.scope, config);
+        }
+    }
+
+    Ok(table)
+}
+
+// GET /api/admin/pricing - Configured pricing models, the global one has scope "*"
+pub async fn list_pricing_models(State(pool): State<AppState>) -> Result<Json<Vec<PricingModelEntry>>, ApiError> {
+    let rows = sqlx::query!("SELECT scope, config, updated_by, updated_at FROM tb_pricing_models ORDER BY scope")
+        .fetch_all(&pool.pool)
+        .await?;
+
+    let entries = rows
+        .into_iter()
+        .map(|r| PricingModelEntry {
+            config: serde_json::from_str(&r.config).unwrap_or(serde_json::Value::String(r.config)),
+            scope: r.scope,
+            updated_by: r.updated_by,
+            updated_at: r.updated_at,
+        })
+        .collect();
+
+    Ok(Json(entries))
+}
+
+// PUT /api/admin/pricing/{scope} - Set the model for an item, or for every item with "default"
+pub async fn set_pricing_model(
+    Path(scope): Path<String>,
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+    Json(payload): Json<PricingConfig>,
+) -> Result<Json<PricingConfig>, ApiError> {
+    let scope = scope_from_path(&scope);
+    payload.validate().map_err(ApiError::validation)?;
+
+    if scope != DEFAULT_SCOPE {
+        validate_item_key(scope)?;
+        let exists = sqlx::query!("SELECT id FROM tb_market_items WHERE item_key = ?", scope)
+            .fetch_optional(&pool.pool)
+            .await?;
+        if exists.is_none() {
+            return Err(ApiError::ItemUnavailable(scope.to_string()));
+        }
+    }
+
+    let config = serde_json::to_string(&payload)
+        .map_err(|e| ApiError::validation(format!("Invalid pricing model: {}", e)))?;
+
+    sqlx::query!(
+        "INSERT INTO tb_pricing_models (scope, config, updated_by) VALUES (?, ?, ?)
+         ON DUPLICATE KEY UPDATE config = VALUES(config), updated_by = VALUES(updated_by)",
+        scope,
+        config,
+        client.name
+    )
+    .execute(&pool.pool)
+    .await?;
+
+    tracing::info!("📈 {} set the pricing model for {} to {}", client.name, scope, config);
+    Ok(Json(payload))
+}
+
+// DELETE /api/admin/pricing/{scope} - Fall back to the global (or built-in) model
+pub async fn delete_pricing_model(
+    Path(scope): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let scope = scope_from_path(&scope);
+
+    let result = sqlx::query!("DELETE FROM tb_pricing_models WHERE scope = ?", scope)
+        .execute(&pool.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(format!("No pricing model set for {}", scope)));
+    }
+
+    tracing::info!("📈 Removed the pricing model for {}", scope);
+    Ok(Json(serde_json::json!({ "success": true, "message": format!("Removed pricing model for {}", scope) })))
+}
@@ -0,0 +1,406 @@
+// api/quotes.rs
+use std::sync::Arc;
+
+use axum::{extract::State, response::Json};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error::ApiError,
+        market::get_market_item,
+        validation::{trade_total, validate_item_key, validate_player_uuid, validate_quantity},
+    },
+    fees::{FeeOperation, FEE_ROUNDING},
+    money::{Money, Rate},
+    pricing::Side,
+    AppState,
+};
+
+/// Signs and checks market quotes. Quotes aren't stored, the signed token
+/// carries everything needed to honour one.
+#[derive(Clone)]
+pub struct QuoteSigner {
+    secret: Arc<[u8]>,
+}
+
+/// What a quote promises, encoded in its id.
+#[derive(Debug, Serialize, Deserialize)]
+struct QuoteClaims {
+    player_uuid: String,
+    side: Side,
+    item_key: String,
+    quantity: i32,
+    price_per_unit: i64,
+    expires_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteRequest {
+    pub player_uuid: String,
+    pub side: Side,
+    pub item_key: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketQuote {
+    pub quote_id: String,
+    pub player_uuid: String,
+    pub side: Side,
+    pub item_key: String,
+    pub quantity: i32,
+    pub price_per_unit: i64,
+    pub gross_amount: i64,
+    pub transaction_fee: i64,
+    pub vat: i64,
+    /// Paid to the seller, or charged to the buyer.
+    pub net_amount: i64,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// How far a trade's unit price may move before it is rejected. Without a
+/// quote or a limit the trade runs at whatever the price is.
+#[derive(Debug)]
+pub struct PriceProtection {
+    pub quote_id: Option<String>,
+    /// Lowest unit price for a sale, highest for a purchase.
+    pub limit_price_per_unit: Option<i64>,
+    /// Allowed move away from the quoted price.
+    pub max_slippage: Option<Rate>,
+}
+
+impl QuoteSigner {
+    pub fn new(secret: &[u8]) -> Self {
+        Self { secret: secret.into() }
+    }
+
+    /// A per-process key, for when no `quote_secret` is configured.
+    pub fn random() -> Self {
+        let secret: Vec<u8> = [Uuid::new_v4(), Uuid::new_v4()]
+            .iter()
+            .flat_map(|id| id.into_bytes())
+            .collect();
+        Self::new(&secret)
+    }
+
+    fn mac(&self, payload: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        mac
+    }
+
+    /// `hex(claims JSON).hex(HMAC-SHA256(secret, claims JSON))`
+    fn sign(&self, claims: &QuoteClaims) -> String {
+        let payload = serde_json::to_vec(claims).expect("quote claims always serialize");
+        let signature = self.mac(&payload).finalize().into_bytes();
+        format!("{}.{}", hex::encode(&payload), hex::encode(signature))
+    }
+
+    fn verify(&self, quote_id: &str) -> Option<QuoteClaims> {
+        let (payload, signature) = quote_id.split_once('.')?;
+        let payload = hex::decode(payload).ok()?;
+        let signature = hex::decode(signature).ok()?;
+        self.mac(&payload).verify_slice(&signature).ok()?;
+        serde_json::from_slice(&payload).ok()
+    }
+}
+
+impl PriceProtection {
+    /// The worst unit price the player accepts for this trade, if any. A quote
+    /// must be unexpired and for exactly this player, item, side and quantity.
+    pub fn limit(
+        &self,
+        signer: &QuoteSigner,
+        player_uuid: &str,
+        side: Side,
+        item_key: &str,
+        quantity: i32,
+    ) -> Result<Option<i64>, ApiError> {
+        if self.limit_price_per_unit.is_some_and(|limit| limit <= 0) {
+            return Err(ApiError::validation("Price limit must be greater than 0"));
+        }
+
+        let Some(quote_id) = &self.quote_id else {
+            if self.max_slippage.is_some() {
+                return Err(ApiError::validation("max_slippage needs a quote_id"));
+            }
+            return Ok(self.limit_price_per_unit);
+        };
+
+        let claims = signer
+            .verify(quote_id)
+            .ok_or_else(|| ApiError::validation("Invalid quote"))?;
+        if claims.expires_at < Utc::now().timestamp() {
+            return Err(ApiError::validation("Quote has expired, request a new one"));
+        }
+        let matches = claims.player_uuid.eq_ignore_ascii_case(player_uuid)
+            && claims.side == side
+            && claims.item_key == item_key
+            && claims.quantity == quantity;
+        if !matches {
+            return Err(ApiError::validation("Quote is for a different trade"));
+        }
+
+        let quoted = Money::new(claims.price_per_unit);
+        let allowance = self.max_slippage.unwrap_or(Rate::ZERO).apply(quoted, FEE_ROUNDING)?;
+        let limit = match side {
+            Side::Sell => quoted.checked_sub(allowance)?.amount(),
+            Side::Buy => quoted.checked_add(allowance)?.amount(),
+        };
+
+        // With an explicit limit as well, the stricter one wins
+        Ok(Some(match (side, self.limit_price_per_unit) {
+            (Side::Sell, Some(explicit)) => limit.max(explicit),
+            (Side::Buy, Some(explicit)) => limit.min(explicit),
+            (_, None) => limit,
+        }))
+    }
+}
+
+/// Rejects a trade whose unit price is worse for the player than `limit`.
+pub fn check_price_limit(side: Side, limit: Option<i64>, current: i64) -> Result<(), ApiError> {
+    let Some(limit) = limit else {
+        return Ok(());
+    };
+    let moved = match side {
+        Side::Sell => current < limit,
+        Side::Buy => current > limit,
+    };
+    if moved {
+        return Err(ApiError::PriceMoved { limit, current });
+    }
+    Ok(())
+}
+
+// POST /api/market/quote - Signed, short-lived price and fees for a sale or purchase
+pub async fn create_quote(
+    State(pool): State<AppState>,
+    Json(payload): Json<QuoteRequest>,
+) -> Result<Json<MarketQuote>, ApiError> {
+    validate_player_uuid(&payload.player_uuid)?;
+    validate_item_key(&payload.item_key)?;
+    validate_quantity(payload.quantity)?;
+
+    let config = pool.config.current();
+
+    let item = get_market_item(&pool.pool, &payload.item_key)
+        .await?
+        .ok_or_else(|| ApiError::ItemUnavailable(payload.item_key.clone()))?;
+    let price_per_unit = match payload.side {
+        Side::Sell => item.current_sell_price,
+        Side::Buy => item.current_buy_price,
+    };
+    let gross = trade_total(price_per_unit, payload.quantity)?;
+
+    let fees = match payload.side {
+        Side::Sell => config.calculate_market_fees(&config.fee_schedule(FeeOperation::MarketSell), gross)?,
+        Side::Buy => config.calculate_market_buy_fees(&config.fee_schedule(FeeOperation::MarketBuy), gross)?,
+    };
+
+    // Whole seconds, so the response matches what the quote id encodes
+    let expires_at = (Utc::now() + Duration::seconds(config.market_quote_ttl_secs)).timestamp();
+    let claims = QuoteClaims {
+        player_uuid: payload.player_uuid,
+        side: payload.side,
+        item_key: payload.item_key,
+        quantity: payload.quantity,
+        price_per_unit,
+        expires_at,
+    };
+
+    Ok(Json(MarketQuote {
+        quote_id: pool.quotes.sign(&claims),
+        player_uuid: claims.player_uuid,
+        side: claims.side,
+        item_key: claims.item_key,
+        quantity: claims.quantity,
+        price_per_unit,
+        gross_amount: fees.gross_amount.amount(),
+        transaction_fee: fees.transaction_fee.amount(),
+        vat: fees.vat.amount(),
+        net_amount: fees.net_amount.amount(),
+        expires_at: DateTime::from_timestamp(expires_at, 0).unwrap_or_default(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYER: &str = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+
+    fn claims(side: Side, price_per_unit: i64, expires_at: i64) -> QuoteClaims {
+        QuoteClaims {
+            player_uuid: PLAYER.to_string(),
+            side,
+            item_key: "diamond".to_string(),
+            quantity: 64,
+            price_per_unit,
+            expires_at,
+        }
+    }
+
+    fn quote(signer: &QuoteSigner, side: Side, price_per_unit: i64) -> String {
+        signer.sign(&claims(side, price_per_unit, Utc::now().timestamp() + 60))
+    }
+
+    fn protection(quote_id: Option<String>, limit: Option<i64>, max_slippage: Option<Rate>) -> PriceProtection {
+        PriceProtection { quote_id, limit_price_per_unit: limit, max_slippage }
+    }
+
+    fn rejection(result: Result<Option<i64>, ApiError>) -> String {
+        match result {
+            Err(ApiError::Validation(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_signed_quote_verifies() {
+        let signer = QuoteSigner::new(b"secret");
+        let verified = signer.verify(&quote(&signer, Side::Sell, 1_000)).unwrap();
+        assert_eq!(verified.price_per_unit, 1_000);
+        assert_eq!(verified.item_key, "diamond");
+    }
+
+    #[test]
+    fn a_tampered_quote_is_rejected() {
+        let signer = QuoteSigner::new(b"secret");
+        let quote_id = quote(&signer, Side::Sell, 1_000);
+        let (payload, signature) = quote_id.split_once('.').unwrap();
+
+        // Flipped signature bit
+        let mut flipped = hex::decode(signature).unwrap();
+        flipped[0] ^= 1;
+        assert!(signer.verify(&format!("{}.{}", payload, hex::encode(flipped))).is_none());
+
+        // Better price under the original signature
+        let json = String::from_utf8(hex::decode(payload).unwrap()).unwrap();
+        let raised = hex::encode(json.replace("1000", "9000"));
+        assert!(signer.verify(&format!("{}.{}", raised, signature)).is_none());
+
+        // Signed with another key
+        assert!(QuoteSigner::new(b"other").verify(&quote_id).is_none());
+
+        for malformed in ["", ".", payload, "zz.zz", &format!("{}.", payload)] {
+            assert!(signer.verify(malformed).is_none(), "{malformed} should be rejected");
+        }
+
+        let invalid = protection(Some(format!("{}.{}", raised, signature)), None, None);
+        assert_eq!(rejection(invalid.limit(&signer, PLAYER, Side::Sell, "diamond", 64)), "Invalid quote");
+    }
+
+    #[test]
+    fn an_expired_quote_is_rejected() {
+        let signer = QuoteSigner::new(b"secret");
+        let expired = signer.sign(&claims(Side::Buy, 1_000, Utc::now().timestamp() - 1));
+        let result = protection(Some(expired), None, None).limit(&signer, PLAYER, Side::Buy, "diamond", 64);
+        assert_eq!(rejection(result), "Quote has expired, request a new one");
+    }
+
+    #[test]
+    fn a_quote_only_covers_its_own_trade() {
+        let signer = QuoteSigner::new(b"secret");
+        let quoted = protection(Some(quote(&signer, Side::Sell, 1_000)), None, None);
+
+        let other_player = "8667ba71-b85a-4004-af54-457a9734eed7";
+        for (player, side, item, quantity) in [
+            (other_player, Side::Sell, "diamond", 64),
+            (PLAYER, Side::Buy, "diamond", 64),
+            (PLAYER, Side::Sell, "emerald", 64),
+            (PLAYER, Side::Sell, "diamond", 63),
+        ] {
+            let result = quoted.limit(&signer, player, side, item, quantity);
+            assert_eq!(rejection(result), "Quote is for a different trade");
+        }
+
+        // UUIDs match case-insensitively like the database does
+        let upper = PLAYER.to_ascii_uppercase();
+        assert_eq!(quoted.limit(&signer, &upper, Side::Sell, "diamond", 64).unwrap(), Some(1_000));
+    }
+
+    #[test]
+    fn sell_slippage_lowers_the_floor() {
+        let signer = QuoteSigner::new(b"secret");
+        let slipped = protection(Some(quote(&signer, Side::Sell, 1_000)), None, Some(Rate::from_bps(500)));
+        let limit = slipped.limit(&signer, PLAYER, Side::Sell, "diamond", 64).unwrap();
+        assert_eq!(limit, Some(950));
+
+        assert!(check_price_limit(Side::Sell, limit, 1_200).is_ok());
+        assert!(check_price_limit(Side::Sell, limit, 950).is_ok());
+        assert!(matches!(
+            check_price_limit(Side::Sell, limit, 949),
+            Err(ApiError::PriceMoved { limit: 950, current: 949 })
+        ));
+    }
+
+    #[test]
+    fn buy_slippage_raises_the_ceiling() {
+        let signer = QuoteSigner::new(b"secret");
+        let slipped = protection(Some(quote(&signer, Side::Buy, 1_000)), None, Some(Rate::from_bps(500)));
+        let limit = slipped.limit(&signer, PLAYER, Side::Buy, "diamond", 64).unwrap();
+        assert_eq!(limit, Some(1_050));
+
+        assert!(check_price_limit(Side::Buy, limit, 800).is_ok());
+        assert!(check_price_limit(Side::Buy, limit, 1_050).is_ok());
+        assert!(matches!(
+            check_price_limit(Side::Buy, limit, 1_051),
+            Err(ApiError::PriceMoved { limit: 1_050, current: 1_051 })
+        ));
+    }
+
+    #[test]
+    fn slippage_allowance_rounds_down() {
+        // 5% of 999 is 49.95, which allows 49
+        let signer = QuoteSigner::new(b"secret");
+        let five_percent = Some(Rate::from_bps(500));
+
+        let sell = protection(Some(quote(&signer, Side::Sell, 999)), None, five_percent);
+        assert_eq!(sell.limit(&signer, PLAYER, Side::Sell, "diamond", 64).unwrap(), Some(950));
+        let buy = protection(Some(quote(&signer, Side::Buy, 999)), None, five_percent);
+        assert_eq!(buy.limit(&signer, PLAYER, Side::Buy, "diamond", 64).unwrap(), Some(1_048));
+    }
+
+    #[test]
+    fn without_slippage_the_quoted_price_is_the_limit() {
+        let signer = QuoteSigner::new(b"secret");
+        let exact = protection(Some(quote(&signer, Side::Sell, 1_000)), None, None);
+        let limit = exact.limit(&signer, PLAYER, Side::Sell, "diamond", 64).unwrap();
+        assert_eq!(limit, Some(1_000));
+        assert!(check_price_limit(Side::Sell, limit, 999).is_err());
+    }
+
+    #[test]
+    fn the_stricter_of_quote_and_explicit_limit_wins() {
+        let signer = QuoteSigner::new(b"secret");
+        let slippage = Some(Rate::from_bps(500));
+
+        let sell = protection(Some(quote(&signer, Side::Sell, 1_000)), Some(980), slippage);
+        assert_eq!(sell.limit(&signer, PLAYER, Side::Sell, "diamond", 64).unwrap(), Some(980));
+        let loose_sell = protection(Some(quote(&signer, Side::Sell, 1_000)), Some(900), slippage);
+        assert_eq!(loose_sell.limit(&signer, PLAYER, Side::Sell, "diamond", 64).unwrap(), Some(950));
+
+        let buy = protection(Some(quote(&signer, Side::Buy, 1_000)), Some(1_020), slippage);
+        assert_eq!(buy.limit(&signer, PLAYER, Side::Buy, "diamond", 64).unwrap(), Some(1_020));
+        let loose_buy = protection(Some(quote(&signer, Side::Buy, 1_000)), Some(1_100), slippage);
+        assert_eq!(loose_buy.limit(&signer, PLAYER, Side::Buy, "diamond", 64).unwrap(), Some(1_050));
+    }
+
+    #[test]
+    fn limits_without_a_quote() {
+        let signer = QuoteSigner::new(b"secret");
+
+        assert_eq!(protection(None, None, None).limit(&signer, PLAYER, Side::Buy, "diamond", 64).unwrap(), None);
+        assert!(check_price_limit(Side::Buy, None, i64::MAX).is_ok());
+        assert_eq!(protection(None, Some(500), None).limit(&signer, PLAYER, Side::Sell, "diamond", 64).unwrap(), Some(500));
+
+        let slippage_only = protection(None, None, Some(Rate::from_bps(100)));
+        assert_eq!(rejection(slippage_only.limit(&signer, PLAYER, Side::Sell, "diamond", 64)), "max_slippage needs a quote_id");
+        let zero_limit = protection(None, Some(0), None);
+        assert_eq!(rejection(zero_limit.limit(&signer, PLAYER, Side::Sell, "diamond", 64)), "Price limit must be greater than 0");
+    }
+}
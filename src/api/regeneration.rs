@@ -0,0 +1,297 @@
+// api/regeneration.rs
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Extension, Path, State},
+    response::Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::{
+    api::{
+        auth::ApiClient,
+        config::set_config_value,
+        error::ApiError,
+        price_history::{record_price, PriceSource},
+        pricing::load_pricing_table,
+        validation::validate_item_key,
+        ConfigManager,
+    },
+    pricing::{volume_damped_decay, MarketState},
+    AppState,
+};
+
+/// What started a regeneration run.
+#[derive(Debug, Clone, Copy)]
+pub enum RunTrigger {
+    Scheduled,
+    Manual,
+}
+
+impl RunTrigger {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RunTrigger::Scheduled => "SCHEDULED",
+            RunTrigger::Manual => "MANUAL",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegenerationRun {
+    pub id: i64,
+    pub trigger_type: String,
+    pub triggered_by: Option<String>,
+    pub status: String,
+    pub items_updated: i32,
+    pub decay_rate: f64,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegenerationStatus {
+    pub paused: bool,
+    pub interval_secs: u64,
+    pub decay_rate: f64,
+    pub max_multiplier: f64,
+    pub volume_reference: f64,
+    pub last_run: Option<RegenerationRun>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemDecayRequest {
+    /// `null` removes the override so the item uses `regeneration_decay_rate`.
+    pub decay_rate: Option<f64>,
+}
+
+/// Pulls every item toward its base price in one transaction. Items traded
+/// since the last tick recover more slowly, and nothing ends above
+/// `regeneration_max_multiplier`. Returns how many items changed.
+async fn apply_regeneration(pool: &MySqlPool, config: &ConfigManager) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let items = sqlx::query!(
+        "SELECT item_key, base_price, price_multiplier, min_multiplier, max_multiplier, regeneration_decay FROM tb_market_items FOR UPDATE"
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let volume_rows = sqlx::query!(
+        r#"SELECT item_key, transaction_type, CAST(SUM(quantity) AS SIGNED) as "quantity!: i64"
+         FROM tb_market_transactions WHERE timestamp >= DATE_SUB(NOW(), INTERVAL ? SECOND)
+         GROUP BY item_key, transaction_type"#,
+        config.regeneration_interval_secs
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut volumes: HashMap<String, (i64, i64)> = HashMap::new();
+    for row in volume_rows {
+        let entry = volumes.entry(row.item_key).or_default();
+        match row.transaction_type.as_str() {
+            "SELL" => entry.0 += row.quantity,
+            _ => entry.1 += row.quantity,
+        }
+    }
+
+    let pricing = load_pricing_table(&mut tx).await?;
+    let mut updated = 0;
+
+    for item in &items {
+        let (sold, bought) = volumes.get(&item.item_key).copied().unwrap_or_default();
+        let state = MarketState {
+            base_price: item.base_price,
+            multiplier: item.price_multiplier,
+            min_multiplier: item.min_multiplier,
+            max_multiplier: item.max_multiplier.min(config.regeneration_max_multiplier),
+            recent_sold: sold,
+            recent_bought: bought,
+        };
+        let decay = volume_damped_decay(
+            item.regeneration_decay.unwrap_or(config.regeneration_decay_rate),
+            sold + bought,
+            config.regeneration_volume_reference,
+        );
+
+        let model = pricing.for_item(&item.item_key);
+        let multiplier = model.regenerate(&state, decay);
+        if multiplier == item.price_multiplier {
+            continue;
+        }
+        let (sell_price, buy_price) = model.prices(item.base_price, multiplier);
+
+        sqlx::query!(
+            "UPDATE tb_market_items SET price_multiplier = ?, current_sell_price = ?, current_buy_price = ? WHERE item_key = ?",
+            multiplier,
+            sell_price,
+            buy_price,
+            item.item_key
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Snapshot the new prices for the history charts
+        record_price(&mut tx, &item.item_key, multiplier, sell_price, buy_price, PriceSource::Regeneration).await?;
+        updated += 1;
+    }
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+async fn get_run(pool: &MySqlPool, id: u64) -> Result<RegenerationRun, sqlx::Error> {
+    sqlx::query_as!(
+        RegenerationRun,
+        "SELECT id, trigger_type, triggered_by, status, items_updated, decay_rate, error, started_at, finished_at FROM tb_regeneration_runs WHERE id = ?",
+        id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Runs a regeneration tick and logs it to `tb_regeneration_runs`, including failures.
+pub async fn regenerate_prices(
+    pool: &MySqlPool,
+    config: &ConfigManager,
+    trigger: RunTrigger,
+    triggered_by: Option<&str>,
+) -> Result<RegenerationRun, sqlx::Error> {
+    let started_at = Utc::now();
+    let result = apply_regeneration(pool, config).await;
+
+    let (status, items_updated, error) = match &result {
+        Ok(count) => ("SUCCESS", *count as i32, None),
+        Err(e) => ("FAILED", 0, Some(e.to_string())),
+    };
+
+    let id = sqlx::query!(
+        "INSERT INTO tb_regeneration_runs (trigger_type, triggered_by, status, items_updated, decay_rate, error, started_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        trigger.as_str(),
+        triggered_by,
+        status,
+        items_updated,
+        config.regeneration_decay_rate,
+        error,
+        started_at
+    )
+    .execute(pool)
+    .await?
+    .last_insert_id();
+
+    result?;
+    get_run(pool, id).await
+}
+
+async fn last_successful_run(pool: &MySqlPool) -> Result<Option<RegenerationRun>, sqlx::Error> {
+    sqlx::query_as!(
+        RegenerationRun,
+        "SELECT id, trigger_type, triggered_by, status, items_updated, decay_rate, error, started_at, finished_at
+         FROM tb_regeneration_runs WHERE status = 'SUCCESS' ORDER BY id DESC LIMIT 1"
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+/// When the next scheduled run is due, counted from the last successful one.
+/// `None` means regeneration has never run and is due now.
+pub async fn next_run_at(pool: &MySqlPool, config: &ConfigManager) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    Ok(last_successful_run(pool)
+        .await?
+        .map(|run| run.started_at + Duration::seconds(config.regeneration_interval_secs as i64)))
+}
+
+// GET /api/admin/regeneration - Schedule, settings and the last run
+pub async fn get_regeneration_status(State(pool): State<AppState>) -> Result<Json<RegenerationStatus>, ApiError> {
+    let config = pool.config.current();
+
+    Ok(Json(RegenerationStatus {
+        paused: config.regeneration_paused,
+        interval_secs: config.regeneration_interval_secs,
+        decay_rate: config.regeneration_decay_rate,
+        max_multiplier: config.regeneration_max_multiplier,
+        volume_reference: config.regeneration_volume_reference,
+        next_run_at: next_run_at(&pool.pool, &config).await?,
+        last_run: last_successful_run(&pool.pool).await?,
+    }))
+}
+
+// GET /api/admin/regeneration/runs - Most recent regeneration runs, failed ones included
+pub async fn list_regeneration_runs(State(pool): State<AppState>) -> Result<Json<Vec<RegenerationRun>>, ApiError> {
+    let runs = sqlx::query_as!(
+        RegenerationRun,
+        "SELECT id, trigger_type, triggered_by, status, items_updated, decay_rate, error, started_at, finished_at
+         FROM tb_regeneration_runs ORDER BY id DESC LIMIT 100"
+    )
+    .fetch_all(&pool.pool)
+    .await?;
+
+    Ok(Json(runs))
+}
+
+// POST /api/admin/regeneration/run - Regenerate prices now, even while paused
+pub async fn trigger_regeneration(
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+) -> Result<Json<RegenerationRun>, ApiError> {
+    let config = pool.config.current();
+    let run = regenerate_prices(&pool.pool, &config, RunTrigger::Manual, Some(&client.name)).await?;
+
+    tracing::info!("🔄 {} regenerated prices for {} items", client.name, run.items_updated);
+    Ok(Json(run))
+}
+
+// POST /api/admin/regeneration/pause - Stop scheduled runs until resumed
+pub async fn pause_regeneration(
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+) -> Result<Json<RegenerationStatus>, ApiError> {
+    set_config_value(&pool, "regeneration_paused", 1.0, &client.name).await?;
+    get_regeneration_status(State(pool)).await
+}
+
+// POST /api/admin/regeneration/resume - Resume scheduled runs
+pub async fn resume_regeneration(
+    State(pool): State<AppState>,
+    Extension(client): Extension<ApiClient>,
+) -> Result<Json<RegenerationStatus>, ApiError> {
+    set_config_value(&pool, "regeneration_paused", 0.0, &client.name).await?;
+    get_regeneration_status(State(pool)).await
+}
+
+// PUT /api/admin/regeneration/items/{key} - Override the decay rate for one item
+pub async fn set_item_decay(
+    Path(item_key): Path<String>,
+    State(pool): State<AppState>,
+    Json(payload): Json<ItemDecayRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    validate_item_key(&item_key)?;
+    if payload.decay_rate.is_some_and(|decay_rate| !(0.0..=1.0).contains(&decay_rate)) {
+        return Err(ApiError::validation("Decay rate must be between 0 and 1"));
+    }
+
+    let result = sqlx::query!(
+        "UPDATE tb_market_items SET regeneration_decay = ? WHERE item_key = ?",
+        payload.decay_rate,
+        item_key
+    )
+    .execute(&pool.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        let exists = sqlx::query!("SELECT id FROM tb_market_items WHERE item_key = ?", item_key)
+            .fetch_optional(&pool.pool)
+            .await?;
+        if exists.is_none() {
+            return Err(ApiError::ItemUnavailable(item_key));
+        }
+    }
+
+    tracing::info!("🔄 Regeneration decay for {} set to {:?}", item_key, payload.decay_rate);
+    Ok(Json(serde_json::json!({ "success": true, "item_key": item_key, "decay_rate": payload.decay_rate })))
+}
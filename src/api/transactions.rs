@@ -0,0 +1,249 @@
+// api/transactions.rs
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::{
+    api::{
+        error::ApiError,
+        ledger::Reason,
+        user::get_user_by_uuid,
+        validation::{validate_item_key, validate_page_limit, validate_player_uuid},
+    },
+    AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionQuery {
+    /// Ledger reason, e.g. `market_sell`, `transfer` or `p2p_payment`.
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub item_key: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// `next_cursor` from the previous page.
+    pub cursor: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// One journal as seen by the player: what it did to their wallet and bank.
+#[derive(Debug, Serialize)]
+pub struct TransactionEntry {
+    pub journal_id: i64,
+    pub reason: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub wallet_change: i64,
+    pub bank_change: i64,
+    /// Fees and VAT the player paid on this journal.
+    pub fee: i64,
+    pub item_key: Option<String>,
+    pub quantity: Option<i32>,
+    pub price_per_unit: Option<i64>,
+    /// The other player on a payment.
+    pub counterparty: Option<String>,
+}
+
+/// Sums over every entry matching the filters, not just the current page.
+#[derive(Debug, Serialize)]
+pub struct TransactionTotals {
+    pub entries: i64,
+    pub wallet_change: i64,
+    pub bank_change: i64,
+    pub market_earned: i64,
+    pub market_spent: i64,
+    pub fees_paid: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransactionHistory {
+    pub player_uuid: String,
+    pub transactions: Vec<TransactionEntry>,
+    pub next_cursor: Option<i64>,
+    pub totals: TransactionTotals,
+}
+
+struct TransactionFilter<'a> {
+    reason: Option<&'static str>,
+    item_key: Option<&'a str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+async fn get_transactions(
+    pool: &MySqlPool,
+    uuid: &str,
+    filter: &TransactionFilter<'_>,
+    cursor: Option<i64>,
+    limit: i64,
+) -> Result<Vec<TransactionEntry>, sqlx::Error> {
+    // Journals reference the market transaction or payment row that caused them
+    let entries = sqlx::query_as!(
+        TransactionEntry,
+        r#"SELECT
+            j.id as "journal_id!: i64",
+            j.reason,
+            j.created_at,
+            e.wallet_change as "wallet_change!: i64",
+            e.bank_change as "bank_change!: i64",
+            CAST(CASE
+                WHEN j.reason = 'P2P_PAYMENT' THEN IF(p.sender_uuid = ?, p.fee, 0)
+                ELSE COALESCE((SELECT SUM(f.amount) FROM tb_ledger f
+                    WHERE f.journal_id = j.id AND f.account_type = 'SYSTEM'
+                    AND f.account_id IN ('market_fees', 'vat', 'transfer_fees')), 0)
+            END AS SIGNED) as "fee!: i64",
+            m.item_key as "item_key?",
+            m.quantity as "quantity?",
+            m.price_per_unit as "price_per_unit?",
+            IF(p.sender_uuid = ?, p.recipient_uuid, p.sender_uuid) as "counterparty?: String"
+         FROM (
+            SELECT journal_id,
+                CAST(SUM(CASE WHEN account_type = 'WALLET' THEN amount ELSE 0 END) AS SIGNED) as wallet_change,
+                CAST(SUM(CASE WHEN account_type = 'BANK' THEN amount ELSE 0 END) AS SIGNED) as bank_change
+            FROM tb_ledger
+            WHERE account_id = ? AND account_type IN ('WALLET', 'BANK') AND (? IS NULL OR journal_id < ?)
+            GROUP BY journal_id
+         ) e
+         JOIN tb_ledger_journal j ON j.id = e.journal_id
+         LEFT JOIN tb_market_transactions m
+            ON j.reason IN ('MARKET_SELL', 'MARKET_BUY') AND m.id = CAST(j.reference AS UNSIGNED)
+         LEFT JOIN tb_player_payments p
+            ON j.reason = 'P2P_PAYMENT' AND p.id = CAST(j.reference AS UNSIGNED)
+         WHERE (? IS NULL OR j.reason = ?)
+         AND (? IS NULL OR m.item_key = ?)
+         AND (? IS NULL OR j.created_at >= ?)
+         AND (? IS NULL OR j.created_at < ?)
+         ORDER BY j.id DESC
+         LIMIT ?"#,
+        uuid,
+        uuid,
+        uuid,
+        cursor,
+        cursor,
+        filter.reason,
+        filter.reason,
+        filter.item_key,
+        filter.item_key,
+        filter.from,
+        filter.from,
+        filter.to,
+        filter.to,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+async fn get_transaction_totals(
+    pool: &MySqlPool,
+    uuid: &str,
+    filter: &TransactionFilter<'_>,
+) -> Result<TransactionTotals, sqlx::Error> {
+    let totals = sqlx::query_as!(
+        TransactionTotals,
+        r#"SELECT
+            COUNT(*) as "entries!: i64",
+            CAST(COALESCE(SUM(t.wallet_change), 0) AS SIGNED) as "wallet_change!: i64",
+            CAST(COALESCE(SUM(t.bank_change), 0) AS SIGNED) as "bank_change!: i64",
+            CAST(COALESCE(SUM(CASE WHEN t.reason = 'MARKET_SELL' THEN t.wallet_change END), 0) AS SIGNED) as "market_earned!: i64",
+            CAST(COALESCE(-SUM(CASE WHEN t.reason = 'MARKET_BUY' THEN t.wallet_change END), 0) AS SIGNED) as "market_spent!: i64",
+            CAST(COALESCE(SUM(t.fee), 0) AS SIGNED) as "fees_paid!: i64"
+         FROM (
+            SELECT j.reason, e.wallet_change, e.bank_change,
+                CASE
+                    WHEN j.reason = 'P2P_PAYMENT' THEN IF(p.sender_uuid = ?, p.fee, 0)
+                    ELSE COALESCE((SELECT SUM(f.amount) FROM tb_ledger f
+                        WHERE f.journal_id = j.id AND f.account_type = 'SYSTEM'
+                        AND f.account_id IN ('market_fees', 'vat', 'transfer_fees')), 0)
+                END as fee
+            FROM (
+                SELECT journal_id,
+                    SUM(CASE WHEN account_type = 'WALLET' THEN amount ELSE 0 END) as wallet_change,
+                    SUM(CASE WHEN account_type = 'BANK' THEN amount ELSE 0 END) as bank_change
+                FROM tb_ledger
+                WHERE account_id = ? AND account_type IN ('WALLET', 'BANK')
+                GROUP BY journal_id
+            ) e
+            JOIN tb_ledger_journal j ON j.id = e.journal_id
+            LEFT JOIN tb_market_transactions m
+                ON j.reason IN ('MARKET_SELL', 'MARKET_BUY') AND m.id = CAST(j.reference AS UNSIGNED)
+            LEFT JOIN tb_player_payments p
+                ON j.reason = 'P2P_PAYMENT' AND p.id = CAST(j.reference AS UNSIGNED)
+            WHERE (? IS NULL OR j.reason = ?)
+            AND (? IS NULL OR m.item_key = ?)
+            AND (? IS NULL OR j.created_at >= ?)
+            AND (? IS NULL OR j.created_at < ?)
+         ) t"#,
+        uuid,
+        uuid,
+        filter.reason,
+        filter.reason,
+        filter.item_key,
+        filter.item_key,
+        filter.from,
+        filter.from,
+        filter.to,
+        filter.to
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(totals)
+}
+
+// GET /api/user/{uuid}/transactions - Paginated trades, transfers and payments for a player
+pub async fn get_user_transactions(
+    Path(uuid): Path<String>,
+    Query(query): Query<TransactionQuery>,
+    State(pool): State<AppState>,
+) -> Result<Json<TransactionHistory>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    let reason = match query.kind.as_deref() {
+        Some(kind) => Some(Reason::parse(kind).ok_or_else(|| {
+            let valid: Vec<String> = Reason::ALL.iter().map(|r| r.as_str().to_lowercase()).collect();
+            ApiError::validation(format!("Unknown type '{}', expected one of: {}", kind, valid.join(", ")))
+        })?),
+        None => None,
+    };
+    if let Some(item_key) = &query.item_key {
+        validate_item_key(item_key)?;
+    }
+    if query.from.zip(query.to).is_some_and(|(from, to)| from >= to) {
+        return Err(ApiError::validation("'from' must be before 'to'"));
+    }
+    let limit = validate_page_limit(query.limit)?;
+
+    if get_user_by_uuid(&pool.pool, &uuid).await?.is_none() {
+        return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+    }
+
+    let filter = TransactionFilter {
+        reason: reason.map(|r| r.as_str()),
+        item_key: query.item_key.as_deref(),
+        from: query.from,
+        to: query.to,
+    };
+
+    // One extra row tells us whether there is another page
+    let mut transactions = get_transactions(&pool.pool, &uuid, &filter, query.cursor, limit + 1).await?;
+    let next_cursor = if transactions.len() as i64 > limit {
+        transactions.truncate(limit as usize);
+        transactions.last().map(|entry| entry.journal_id)
+    } else {
+        None
+    };
+    let totals = get_transaction_totals(&pool.pool, &uuid, &filter).await?;
+
+    Ok(Json(TransactionHistory {
+        player_uuid: uuid,
+        transactions,
+        next_cursor,
+        totals,
+    }))
+}
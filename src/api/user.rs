@@ -1,13 +1,22 @@
 // api/user.rs
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
     response::Json,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
+use sqlx::{MySqlConnection, MySqlPool};
 
-use crate::{api::ConfigManager, AppState};
+use crate::{
+    api::{
+        bank::has_live_session,
+        error::ApiError,
+        ledger::{Account, Journal, Reason, SystemAccount},
+        validation::{validate_amount, validate_player_name, validate_player_uuid},
+    },
+    fees::FeeOperation,
+    money::Money,
+    AppState,
+};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct User {
@@ -25,6 +34,22 @@ pub struct UserResponse {
     pub is_bank_open: i8,
 }
 
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    pub created: bool,
+    /// Set when the player logged in under a new name.
+    pub previous_name: Option<String>,
+    pub user: UserResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NameChange {
+    pub player_uuid: String,
+    pub old_name: String,
+    pub new_name: String,
+    pub changed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TransferRequest {
     pub from: String,    // "wallet" or "bank"
@@ -42,6 +67,29 @@ pub struct TransferResponse {
     pub amount_transferred: i64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PayRequest {
+    pub recipient_uuid: String,
+    pub amount: i64,
+    #[serde(default = "default_pay_source")]
+    pub source: String,  // "wallet" or "bank"
+}
+
+fn default_pay_source() -> String {
+    "wallet".to_string()
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayResponse {
+    pub success: bool,
+    pub message: String,
+    pub recipient_uuid: String,
+    pub new_wallet: i64,
+    pub new_bank: i64,
+    pub fee_charged: i64,
+    pub amount_paid: i64,
+}
+
 async fn insert_user(pool: &MySqlPool, user: User) -> Result<u64, sqlx::Error> {
     let result = sqlx::query!(
         "INSERT INTO tb_user (player_uuid, player_name) VALUES (?, ?)",
@@ -66,6 +114,58 @@ pub async fn get_user_by_uuid(pool: &MySqlPool, uuid: &str) -> Result<Option<Use
     Ok(user)
 }
 
+/// Names aren't unique in storage, a renamed player keeps their old name until
+/// they log in again. The most recently updated row is the likely owner.
+pub async fn get_user_by_name(pool: &MySqlPool, name: &str) -> Result<Option<UserResponse>, sqlx::Error> {
+    let user = sqlx::query_as!(
+        UserResponse,
+        "SELECT id, player_uuid, player_name, wallet, bank, is_bank_open FROM tb_user WHERE player_name = ? ORDER BY updated_at DESC, id DESC LIMIT 1",
+        name
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(user)
+}
+
+async fn get_name_history(pool: &MySqlPool, uuid: &str) -> Result<Vec<NameChange>, sqlx::Error> {
+    let changes = sqlx::query_as!(
+        NameChange,
+        "SELECT player_uuid, old_name, new_name, changed_at FROM tb_player_name_history WHERE player_uuid = ? ORDER BY id DESC",
+        uuid
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(changes)
+}
+
+async fn find_name_changes(pool: &MySqlPool, name: &str) -> Result<Vec<NameChange>, sqlx::Error> {
+    let changes = sqlx::query_as!(
+        NameChange,
+        "SELECT player_uuid, old_name, new_name, changed_at FROM tb_player_name_history WHERE old_name = ? OR new_name = ? ORDER BY id DESC LIMIT 100",
+        name,
+        name
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(changes)
+}
+
+/// Same as `get_user_by_uuid`, but takes a row lock for the rest of the transaction.
+pub async fn lock_user(conn: &mut MySqlConnection, uuid: &str) -> Result<Option<UserResponse>, sqlx::Error> {
+    let user = sqlx::query_as!(
+        UserResponse,
+        "SELECT id, player_uuid, player_name, wallet, bank, is_bank_open FROM tb_user WHERE player_uuid = ? FOR UPDATE",
+        uuid
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(user)
+}
+
 async fn get_wallet(pool: &MySqlPool, uuid: &str) -> Result<Option<i64>, sqlx::Error> {
     let record = sqlx::query!(
         "SELECT wallet FROM tb_user WHERE player_uuid = ?",
@@ -87,105 +187,220 @@ async fn get_bank(pool: &MySqlPool, uuid: &str) -> Result<Option<i64>, sqlx::Err
     Ok(record.map(|r| r.bank))
 }
 
-async fn wallet_to_bank_with_fee(pool: &MySqlPool, uuid: &str, amount: i64) -> Result<(u64, i64), sqlx::Error> {
-    let config = ConfigManager::load_from_db(pool).await?;
-    let fee = config.calculate_transfer_fee("wallet", "bank", amount);
-    let total_deducted = amount + fee;
+async fn wallet_to_bank_with_fee(pool: &MySqlPool, uuid: &str, amount: Money, fee: Money) -> Result<u64, ApiError> {
+    let mut tx = pool.begin().await?;
+    let total_deducted = amount.checked_add(fee)?;
 
     let result = sqlx::query!(
-        "UPDATE tb_user SET wallet = wallet - ?, bank = bank + ? WHERE player_uuid = ? AND wallet >= ? AND is_bank_open = 1",
-        total_deducted,
-        amount,
+        "UPDATE tb_user SET wallet = wallet - ?, bank = bank + ? WHERE player_uuid = ? AND wallet >= ? AND is_bank_open = 1
+           AND EXISTS (SELECT 1 FROM tb_bank_sessions s WHERE s.player_uuid = tb_user.player_uuid AND s.closed_at IS NULL AND s.expires_at > NOW())",
+        total_deducted.amount(),
+        amount.amount(),
         uuid,
-        total_deducted
+        total_deducted.amount()
     )
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
 
-    Ok((result.rows_affected(), fee))
+    if result.rows_affected() > 0 {
+        Journal::new(Reason::Transfer)
+            .debit(Account::Wallet(uuid), total_deducted)
+            .credit(Account::Bank(uuid), amount)
+            .credit(Account::System(SystemAccount::TransferFees), fee)
+            .post(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
 }
 
-async fn bank_to_wallet_with_fee(pool: &MySqlPool, uuid: &str, amount: i64) -> Result<(u64, i64), sqlx::Error> {
-    let config = ConfigManager::load_from_db(pool).await?;
-    let fee = config.calculate_transfer_fee("bank", "wallet", amount);
-    let total_deducted = amount + fee;
+async fn bank_to_wallet_with_fee(pool: &MySqlPool, uuid: &str, amount: Money, fee: Money) -> Result<u64, ApiError> {
+    let mut tx = pool.begin().await?;
+    let total_deducted = amount.checked_add(fee)?;
 
     let result = sqlx::query!(
-        "UPDATE tb_user SET bank = bank - ?, wallet = wallet + ? WHERE player_uuid = ? AND bank >= ? AND is_bank_open = 1",
-        total_deducted,
-        amount,
+        "UPDATE tb_user SET bank = bank - ?, wallet = wallet + ? WHERE player_uuid = ? AND bank >= ? AND is_bank_open = 1
+           AND EXISTS (SELECT 1 FROM tb_bank_sessions s WHERE s.player_uuid = tb_user.player_uuid AND s.closed_at IS NULL AND s.expires_at > NOW())",
+        total_deducted.amount(),
+        amount.amount(),
         uuid,
-        total_deducted
+        total_deducted.amount()
     )
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
 
-    Ok((result.rows_affected(), fee))
+    if result.rows_affected() > 0 {
+        Journal::new(Reason::Transfer)
+            .debit(Account::Bank(uuid), total_deducted)
+            .credit(Account::Wallet(uuid), amount)
+            .credit(Account::System(SystemAccount::TransferFees), fee)
+            .post(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
 }
 
 pub async fn create_user(
     State(pool): State<AppState>,
     Json(payload): Json<User>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match insert_user(&pool.pool, payload).await {
-        Ok(user_id) => {
-            tracing::info!("User created successfully with id: {}", user_id);
-            Ok(Json(serde_json::json!({
-                "success": true,
-                "user_id": user_id,
-                "message": "User created successfully"
-            })))
-        },
-        Err(e) => {
-            tracing::error!("Failed to create user: {:?}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        },
+) -> Result<Json<serde_json::Value>, ApiError> {
+    validate_player_uuid(&payload.player_uuid)?;
+    validate_player_name(&payload.player_name)?;
+
+    let uuid = payload.player_uuid.clone();
+    let user_id = insert_user(&pool.pool, payload).await.map_err(|e| {
+        if e.as_database_error().is_some_and(|db| db.is_unique_violation()) {
+            ApiError::Conflict(format!("User {} already exists", uuid))
+        } else {
+            ApiError::from(e)
+        }
+    })?;
+    tracing::info!("User created successfully with id: {}", user_id);
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "user_id": user_id,
+        "message": "User created successfully"
+    })))
+}
+
+// POST /api/user/login - Create the player on first join and keep their name current
+pub async fn login_user(
+    State(pool): State<AppState>,
+    Json(payload): Json<User>,
+) -> Result<Json<LoginResponse>, ApiError> {
+    validate_player_uuid(&payload.player_uuid)?;
+    validate_player_name(&payload.player_name)?;
+
+    let mut tx = pool.pool.begin().await?;
+
+    // IGNORE leaves an existing row alone, so repeated or concurrent logins are safe
+    let created = sqlx::query!(
+        "INSERT IGNORE INTO tb_user (player_uuid, player_name) VALUES (?, ?)",
+        payload.player_uuid,
+        payload.player_name
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    let mut user = lock_user(&mut tx, &payload.player_uuid)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No user found with UUID {}", payload.player_uuid)))?;
+
+    let mut previous_name = None;
+    if user.player_name != payload.player_name {
+        sqlx::query!(
+            "UPDATE tb_user SET player_name = ? WHERE player_uuid = ?",
+            payload.player_name,
+            payload.player_uuid
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO tb_player_name_history (player_uuid, old_name, new_name) VALUES (?, ?, ?)",
+            payload.player_uuid,
+            user.player_name,
+            payload.player_name
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        previous_name = Some(std::mem::replace(&mut user.player_name, payload.player_name));
+    }
+
+    tx.commit().await?;
+
+    if created {
+        tracing::info!("👋 New player {} ({})", user.player_name, user.player_uuid);
+    } else if let Some(previous) = &previous_name {
+        tracing::info!("✏️ Player {} renamed from {} to {}", user.player_uuid, previous, user.player_name);
+    }
+
+    Ok(Json(LoginResponse {
+        created,
+        previous_name,
+        user,
+    }))
+}
+
+// GET /api/user/by-name/{name} - Look up a player by their current name
+pub async fn get_user_by_name_endpoint(
+    Path(name): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<UserResponse>, ApiError> {
+    validate_player_name(&name)?;
+
+    match get_user_by_name(&pool.pool, &name).await? {
+        Some(user) => Ok(Json(user)),
+        None => Err(ApiError::not_found(format!("No user found with name {}", name))),
+    }
+}
+
+// GET /api/user/{uuid}/names - Name changes for a player, newest first
+pub async fn get_user_names(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<Vec<NameChange>>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    if get_user_by_uuid(&pool.pool, &uuid).await?.is_none() {
+        return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
     }
+
+    Ok(Json(get_name_history(&pool.pool, &uuid).await?))
+}
+
+// GET /api/admin/names/{name} - Every recorded rename to or from a name
+pub async fn search_name_history(
+    Path(name): Path<String>,
+    State(pool): State<AppState>,
+) -> Result<Json<Vec<NameChange>>, ApiError> {
+    validate_player_name(&name)?;
+
+    Ok(Json(find_name_changes(&pool.pool, &name).await?))
 }
 
 pub async fn get_user(
     Path(uuid): Path<String>,
     State(pool): State<AppState>,
-) -> Result<Json<UserResponse>, StatusCode> {
-    match get_user_by_uuid(&pool.pool, &uuid).await {
-        Ok(Some(user)) => {
+) -> Result<Json<UserResponse>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    match get_user_by_uuid(&pool.pool, &uuid).await? {
+        Some(user) => {
             tracing::info!("Found user '{}' with UUID {}", user.player_name, uuid);
             Ok(Json(user))
         },
-        Ok(None) => {
+        None => {
             tracing::warn!("No user found with UUID {}", uuid);
-            Err(StatusCode::NOT_FOUND)
-        },
-        Err(e) => {
-            tracing::error!("Database error while fetching user {}: {:?}", uuid, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(ApiError::not_found(format!("No user found with UUID {}", uuid)))
         },
     }
 }
 pub async fn get_user_wallet(
     Path(uuid): Path<String>,
     State(pool): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match get_wallet(&pool.pool, &uuid).await {
-        Ok(Some(wallet)) => Ok(Json(serde_json::json!({ "wallet": wallet }))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Database error while fetching wallet for {}: {:?}", uuid, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        },
+) -> Result<Json<serde_json::Value>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    match get_wallet(&pool.pool, &uuid).await? {
+        Some(wallet) => Ok(Json(serde_json::json!({ "wallet": wallet }))),
+        None => Err(ApiError::not_found(format!("No user found with UUID {}", uuid))),
     }
 }
 pub async fn get_user_bank(
     Path(uuid): Path<String>,
     State(pool): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match get_bank(&pool.pool, &uuid).await {
-        Ok(Some(bank)) => Ok(Json(serde_json::json!({ "bank": bank }))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Database error while fetching bank for {}: {:?}", uuid, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        },
+) -> Result<Json<serde_json::Value>, ApiError> {
+    validate_player_uuid(&uuid)?;
+
+    match get_bank(&pool.pool, &uuid).await? {
+        Some(bank) => Ok(Json(serde_json::json!({ "bank": bank }))),
+        None => Err(ApiError::not_found(format!("No user found with UUID {}", uuid))),
     }
 }
 
@@ -193,61 +408,170 @@ pub async fn transfer_money(
     Path(uuid): Path<String>,
     State(pool): State<AppState>,
     Json(payload): Json<TransferRequest>,
-) -> Result<Json<TransferResponse>, StatusCode> {
-    let transfer_result = match (payload.from.as_str(), payload.to.as_str()) {
-        ("wallet", "bank") => wallet_to_bank_with_fee(&pool.pool, &uuid, payload.amount).await,
-        ("bank", "wallet") => bank_to_wallet_with_fee(&pool.pool, &uuid, payload.amount).await,
-        _ => {
-            return Ok(Json(TransferResponse {
-                success: false,
-                message: "Invalid transfer direction. Use 'wallet' or 'bank'".to_string(),
-                new_wallet: 0,
-                new_bank: 0,
-                fee_charged: 0,
-                amount_transferred: payload.amount,
-            }));
-        }
+) -> Result<Json<TransferResponse>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    validate_amount(payload.amount)?;
+
+    let operation = FeeOperation::transfer(&payload.from, &payload.to)
+        .ok_or_else(|| ApiError::validation("Invalid transfer direction. Use 'wallet' or 'bank'"))?;
+    let amount = Money::new(payload.amount);
+    let fee = pool.config.current().fee_schedule(operation).fee(amount)?;
+
+    let rows_affected = if operation == FeeOperation::WalletToBank {
+        wallet_to_bank_with_fee(&pool.pool, &uuid, amount, fee).await?
+    } else {
+        bank_to_wallet_with_fee(&pool.pool, &uuid, amount, fee).await?
     };
 
-    match transfer_result {
-        Ok((rows_affected, fee)) => {
-            if rows_affected == 0 {
-                // Check if it's a bank access issue or insufficient funds
-                let user = get_user_by_uuid(&pool.pool, &uuid).await.unwrap_or(None);
-                let error_msg = match user {
-                    Some(u) if u.is_bank_open == 0 => "Bank is not open! Visit a bank to access your account".to_string(),
-                    Some(u) => {
-                        let required = payload.amount + fee;
-                        let available = if payload.from == "wallet" { u.wallet } else { u.bank };
-                        format!("Insufficient funds in {} (have: {}, need: {})", payload.from, available, required)
-                    }
-                    None => "User not found".to_string(),
-                };
-                
-                return Ok(Json(TransferResponse {
-                    success: false,
-                    message: error_msg,
-                    new_wallet: 0,
-                    new_bank: 0,
-                    fee_charged: fee,
-                    amount_transferred: payload.amount,
-                }));
-            }
-
-            let user = match get_user_by_uuid(&pool.pool, &uuid).await {
-                Ok(Some(user)) => user,
-                _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
-            };
-
-            Ok(Json(TransferResponse {
-                success: true,
-                message: format!("Transferred {} from {} to {} (fee: {})", payload.amount, payload.from, payload.to, fee),
-                new_wallet: user.wallet,
-                new_bank: user.bank,
-                fee_charged: fee,
-                amount_transferred: payload.amount,
-            }))
+    if rows_affected == 0 {
+        // Check if it's a bank access issue or insufficient funds
+        let u = get_user_by_uuid(&pool.pool, &uuid)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("No user found with UUID {}", uuid)))?;
+        if u.is_bank_open == 0 || !has_live_session(&mut *pool.pool.acquire().await?, &uuid).await? {
+            return Err(ApiError::BankClosed);
         }
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        return Err(ApiError::InsufficientFunds {
+            have: if payload.from == "wallet" { u.wallet } else { u.bank },
+            source: payload.from,
+            need: amount.checked_add(fee)?.amount(),
+        });
     }
-}
\ No newline at end of file
+
+    let user = get_user_by_uuid(&pool.pool, &uuid)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No user found with UUID {}", uuid)))?;
+
+    Ok(Json(TransferResponse {
+        success: true,
+        message: format!("Transferred {} from {} to {} (fee: {})", payload.amount, payload.from, payload.to, fee),
+        new_wallet: user.wallet,
+        new_bank: user.bank,
+        fee_charged: fee.amount(),
+        amount_transferred: payload.amount,
+    }))
+}
+
+// POST /api/user/{uuid}/pay - Player pays another player
+pub async fn pay_player(
+    Path(uuid): Path<String>,
+    State(pool): State<AppState>,
+    Json(payload): Json<PayRequest>,
+) -> Result<Json<PayResponse>, ApiError> {
+    validate_player_uuid(&uuid)?;
+    validate_player_uuid(&payload.recipient_uuid)?;
+    validate_amount(payload.amount)?;
+
+    if payload.recipient_uuid.eq_ignore_ascii_case(&uuid) {
+        return Err(ApiError::validation("You cannot pay yourself"));
+    }
+    if payload.source != "wallet" && payload.source != "bank" {
+        return Err(ApiError::validation("Invalid payment source. Use 'wallet' or 'bank'"));
+    }
+
+    let config = pool.config.current();
+    let amount = Money::new(payload.amount);
+
+    let mut tx = pool.pool.begin().await?;
+    let fee = config.fee_schedule(FeeOperation::P2p).fee(amount)?;
+    let total_deducted = amount.checked_add(fee)?;
+
+    // Lock both rows in a stable order so concurrent payments can't deadlock
+    let accounts = sqlx::query!(
+        "SELECT player_uuid, wallet, bank, is_bank_open FROM tb_user WHERE player_uuid IN (?, ?) ORDER BY player_uuid FOR UPDATE",
+        uuid,
+        payload.recipient_uuid
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // The collation matches UUIDs case-insensitively, so these have to as well
+    let Some(sender) = accounts.iter().find(|a| a.player_uuid.eq_ignore_ascii_case(&uuid)) else {
+        return Err(ApiError::not_found(format!("No user found with UUID {}", uuid)));
+    };
+    if !accounts.iter().any(|a| a.player_uuid.eq_ignore_ascii_case(&payload.recipient_uuid)) {
+        return Err(ApiError::not_found(format!("Recipient {} not found", payload.recipient_uuid)));
+    }
+
+    let available = Money::new(if payload.source == "wallet" { sender.wallet } else { sender.bank });
+    if payload.source == "bank" && (sender.is_bank_open == 0 || !has_live_session(&mut tx, &uuid).await?) {
+        return Err(ApiError::BankClosed);
+    }
+    if available < total_deducted {
+        return Err(ApiError::InsufficientFunds {
+            source: payload.source,
+            have: available.amount(),
+            need: total_deducted.amount(),
+        });
+    }
+
+    if payload.source == "wallet" {
+        sqlx::query!(
+            "UPDATE tb_user SET wallet = wallet - ? WHERE player_uuid = ?",
+            total_deducted.amount(),
+            uuid
+        )
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        sqlx::query!(
+            "UPDATE tb_user SET bank = bank - ? WHERE player_uuid = ?",
+            total_deducted.amount(),
+            uuid
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Recipient always receives into their wallet
+    sqlx::query!(
+        "UPDATE tb_user SET wallet = wallet + ? WHERE player_uuid = ?",
+        amount.amount(),
+        payload.recipient_uuid
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let source = payload.source.to_uppercase();
+    let payment_id = sqlx::query!(
+        "INSERT INTO tb_player_payments (sender_uuid, recipient_uuid, source, amount, fee) VALUES (?, ?, ?, ?, ?)",
+        uuid,
+        payload.recipient_uuid,
+        source,
+        amount.amount(),
+        fee.amount()
+    )
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id();
+
+    let source_account = if payload.source == "wallet" { Account::Wallet(&uuid) } else { Account::Bank(&uuid) };
+    Journal::new(Reason::P2pPayment)
+        .reference(payment_id)
+        .debit(source_account, total_deducted)
+        .credit(Account::Wallet(&payload.recipient_uuid), amount)
+        .credit(Account::System(SystemAccount::P2pFees), fee)
+        .post(&mut *tx)
+        .await?;
+
+    let user = lock_user(&mut tx, &uuid)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No user found with UUID {}", uuid)))?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        "💸 {} paid {} to {} from {} (fee: {})",
+        uuid, payload.amount, payload.recipient_uuid, payload.source, fee
+    );
+
+    Ok(Json(PayResponse {
+        success: true,
+        message: format!("Paid {} to {} (fee: {})", payload.amount, payload.recipient_uuid, fee),
+        recipient_uuid: payload.recipient_uuid,
+        new_wallet: user.wallet,
+        new_bank: user.bank,
+        fee_charged: fee.amount(),
+        amount_paid: payload.amount,
+    }))
+}
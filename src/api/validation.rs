@@ -0,0 +1,60 @@
+// api/validation.rs
+use uuid::Uuid;
+
+use crate::{api::error::ApiError, money::Money};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// Player UUIDs are stored in their 36 character hyphenated form.
+pub fn validate_player_uuid(uuid: &str) -> Result<(), ApiError> {
+    if uuid.len() != 36 || Uuid::try_parse(uuid).is_err() {
+        return Err(ApiError::validation(format!("Invalid player UUID: {}", uuid)));
+    }
+    Ok(())
+}
+
+pub fn validate_quantity(quantity: i32) -> Result<(), ApiError> {
+    if quantity <= 0 {
+        return Err(ApiError::validation("Quantity must be greater than 0"));
+    }
+    Ok(())
+}
+
+pub fn validate_amount(amount: i64) -> Result<(), ApiError> {
+    if amount <= 0 {
+        return Err(ApiError::validation("Amount must be greater than 0"));
+    }
+    Ok(())
+}
+
+pub fn validate_item_key(item_key: &str) -> Result<(), ApiError> {
+    if item_key.is_empty() || item_key.len() > 255 {
+        return Err(ApiError::validation("Item key must be between 1 and 255 characters"));
+    }
+    Ok(())
+}
+
+pub fn validate_player_name(name: &str) -> Result<(), ApiError> {
+    let valid_chars = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if name.is_empty() || name.len() > 16 || !valid_chars {
+        return Err(ApiError::validation(format!("Invalid player name: {}", name)));
+    }
+    Ok(())
+}
+
+/// Unit price times quantity, rejecting trades too large to represent.
+pub fn trade_total(price_per_unit: i64, quantity: i32) -> Result<Money, ApiError> {
+    Money::new(price_per_unit)
+        .checked_mul(quantity as i64)
+        .map_err(|_| ApiError::validation("Trade amount is too large"))
+}
+
+/// Page size for a paginated listing, defaulting when the caller gave none.
+pub fn validate_page_limit(limit: Option<i64>) -> Result<i64, ApiError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+        return Err(ApiError::validation(format!("Limit must be between 1 and {}", MAX_PAGE_SIZE)));
+    }
+    Ok(limit)
+}
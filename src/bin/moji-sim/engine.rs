@@ -0,0 +1,311 @@
+// bin/moji-sim/engine.rs
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use moji::{
+    api::ConfigManager,
+    fees::{FeeOperation, FeeSchedule},
+    money::Money,
+    pricing::{volume_damped_decay, MarketState, PricingTable, Side},
+};
+
+use crate::input::{SimItem, SimTrade};
+
+/// Matches the one hour volume window `update_market_price` queries.
+const VOLUME_WINDOW_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    Trade,
+    Regeneration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PricePoint {
+    pub at: DateTime<Utc>,
+    pub source: PriceSource,
+    pub multiplier: f64,
+    pub sell_price: i64,
+    pub buy_price: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ItemReport {
+    pub item_key: String,
+    pub start_multiplier: f64,
+    pub end_multiplier: f64,
+    pub lowest_multiplier: f64,
+    pub highest_multiplier: f64,
+    pub end_sell_price: i64,
+    pub end_buy_price: i64,
+    pub trades: u64,
+    pub sold: i64,
+    pub bought: i64,
+    pub trajectory: Vec<PricePoint>,
+}
+
+/// Running totals at the end of each hour that had trades.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelinePoint {
+    pub hour: i64,
+    pub trades: u64,
+    pub money_supply_change: i64,
+    pub fee_revenue: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Totals {
+    pub trades: u64,
+    pub skipped_trades: u64,
+    pub regeneration_ticks: u64,
+    /// Money paid out to sellers minus money taken from buyers.
+    pub money_supply_change: i64,
+    pub paid_to_sellers: i64,
+    pub paid_by_buyers: i64,
+    pub transaction_fees: i64,
+    pub vat: i64,
+    pub fee_revenue: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub totals: Totals,
+    pub timeline: Vec<TimelinePoint>,
+    pub items: Vec<ItemReport>,
+}
+
+struct ItemState {
+    item: SimItem,
+    report: ItemReport,
+    /// Trades inside the volume window, oldest first.
+    window: VecDeque<(DateTime<Utc>, Side, i32)>,
+    /// Units sold and bought since the last regeneration tick.
+    since_tick: (i64, i64),
+}
+
+impl ItemState {
+    fn market_state(&self, multiplier: f64, now: DateTime<Utc>) -> MarketState {
+        let since = now - Duration::seconds(VOLUME_WINDOW_SECS);
+        let volume = |side| -> i64 {
+            self.window
+                .iter()
+                .filter(|(at, s, _)| *at >= since && *s == side)
+                .map(|(_, _, quantity)| *quantity as i64)
+                .sum()
+        };
+
+        MarketState {
+            base_price: self.item.base_price,
+            multiplier,
+            min_multiplier: self.item.min_multiplier,
+            max_multiplier: self.item.max_multiplier,
+            recent_sold: volume(Side::Sell),
+            recent_bought: volume(Side::Buy),
+        }
+    }
+
+    fn set_multiplier(&mut self, multiplier: f64, sell_price: i64, buy_price: i64, at: DateTime<Utc>, source: PriceSource) {
+        let report = &mut self.report;
+        report.end_multiplier = multiplier;
+        report.end_sell_price = sell_price;
+        report.end_buy_price = buy_price;
+        report.lowest_multiplier = report.lowest_multiplier.min(multiplier);
+        report.highest_multiplier = report.highest_multiplier.max(multiplier);
+        report.trajectory.push(PricePoint {
+            at,
+            source,
+            multiplier,
+            sell_price,
+            buy_price,
+        });
+    }
+}
+
+/// In-memory market driven by the same pricing and fee code as the server.
+pub struct Simulation {
+    config: ConfigManager,
+    pricing: PricingTable,
+    sell_fees: FeeSchedule,
+    buy_fees: FeeSchedule,
+    items: BTreeMap<String, ItemState>,
+    totals: Totals,
+    timeline: Vec<TimelinePoint>,
+}
+
+impl Simulation {
+    /// `fees` overrides the `tb_config` default schedules, like `tb_fee_schedules` does.
+    pub fn new(
+        config: ConfigManager,
+        pricing: PricingTable,
+        mut fees: HashMap<FeeOperation, FeeSchedule>,
+        items: Vec<SimItem>,
+    ) -> Self {
+        let mut schedule = |operation| fees.remove(&operation).unwrap_or_else(|| config.default_fee_schedule(operation));
+        let (sell_fees, buy_fees) = (schedule(FeeOperation::MarketSell), schedule(FeeOperation::MarketBuy));
+
+        let items = items
+            .into_iter()
+            .map(|item| {
+                let (sell_price, buy_price) = pricing
+                    .for_item(&item.item_key)
+                    .prices(item.base_price, item.price_multiplier);
+                let report = ItemReport {
+                    item_key: item.item_key.clone(),
+                    start_multiplier: item.price_multiplier,
+                    end_multiplier: item.price_multiplier,
+                    lowest_multiplier: item.price_multiplier,
+                    highest_multiplier: item.price_multiplier,
+                    end_sell_price: sell_price,
+                    end_buy_price: buy_price,
+                    trades: 0,
+                    sold: 0,
+                    bought: 0,
+                    trajectory: Vec::new(),
+                };
+                let state = ItemState {
+                    item,
+                    report,
+                    window: VecDeque::new(),
+                    since_tick: (0, 0),
+                };
+                (state.item.item_key.clone(), state)
+            })
+            .collect();
+
+        Self {
+            config,
+            pricing,
+            sell_fees,
+            buy_fees,
+            items,
+            totals: Totals::default(),
+            timeline: Vec::new(),
+        }
+    }
+
+    pub fn run(mut self, trades: &[SimTrade]) -> Report {
+        let started_at = trades.first().map(|t| t.at);
+        let ended_at = trades.last().map(|t| t.at);
+
+        if let Some(start) = started_at {
+            let interval = Duration::seconds(self.config.regeneration_interval_secs.max(1) as i64);
+            let mut next_tick = start + interval;
+            for trade in trades {
+                while trade.at >= next_tick {
+                    self.regenerate(next_tick);
+                    next_tick += interval;
+                }
+                self.execute(trade, start);
+            }
+        }
+
+        Report {
+            started_at,
+            ended_at,
+            totals: self.totals,
+            timeline: self.timeline,
+            items: self.items.into_values().map(|state| state.report).collect(),
+        }
+    }
+
+    /// Mirrors `api::regeneration::regenerate_prices`. Pausing isn't simulated.
+    fn regenerate(&mut self, at: DateTime<Utc>) {
+        let config = &self.config;
+        for state in self.items.values_mut() {
+            let (sold, bought) = std::mem::take(&mut state.since_tick);
+            let market = MarketState {
+                max_multiplier: state.item.max_multiplier.min(config.regeneration_max_multiplier),
+                recent_sold: sold,
+                recent_bought: bought,
+                ..state.market_state(state.report.end_multiplier, at)
+            };
+            let decay = volume_damped_decay(
+                state.item.regeneration_decay.unwrap_or(config.regeneration_decay_rate),
+                sold + bought,
+                config.regeneration_volume_reference,
+            );
+
+            let model = self.pricing.for_item(&state.item.item_key);
+            let multiplier = model.regenerate(&market, decay);
+            if multiplier == state.report.end_multiplier {
+                continue;
+            }
+            let (sell_price, buy_price) = model.prices(state.item.base_price, multiplier);
+            state.set_multiplier(multiplier, sell_price, buy_price, at, PriceSource::Regeneration);
+        }
+        self.totals.regeneration_ticks += 1;
+    }
+
+    /// Mirrors `sell_item`/`buy_item` without the wallet checks.
+    fn execute(&mut self, trade: &SimTrade, start: DateTime<Utc>) {
+        let Some(state) = self.items.get_mut(&trade.item_key) else {
+            self.totals.skipped_trades += 1;
+            return;
+        };
+
+        let price_per_unit = match trade.side {
+            Side::Sell => state.report.end_sell_price,
+            Side::Buy => state.report.end_buy_price,
+        };
+        let fees = Money::new(price_per_unit)
+            .checked_mul(trade.quantity as i64)
+            .and_then(|gross| match trade.side {
+                Side::Sell => self.config.calculate_market_fees(&self.sell_fees, gross),
+                Side::Buy => self.config.calculate_market_buy_fees(&self.buy_fees, gross),
+            });
+        let Ok(fees) = fees else {
+            self.totals.skipped_trades += 1;
+            return;
+        };
+        let (net, transaction_fee, vat) = (fees.net_amount.amount(), fees.transaction_fee.amount(), fees.vat.amount());
+
+        match trade.side {
+            Side::Sell => {
+                self.totals.paid_to_sellers += net;
+                self.totals.money_supply_change += net;
+                state.report.sold += trade.quantity as i64;
+                state.since_tick.0 += trade.quantity as i64;
+            }
+            Side::Buy => {
+                self.totals.paid_by_buyers += net;
+                self.totals.money_supply_change -= net;
+                state.report.bought += trade.quantity as i64;
+                state.since_tick.1 += trade.quantity as i64;
+            }
+        }
+        self.totals.transaction_fees += transaction_fee;
+        self.totals.vat += vat;
+        self.totals.fee_revenue += transaction_fee + vat;
+        self.totals.trades += 1;
+        state.report.trades += 1;
+
+        // The window includes the trade being priced, like the SQL sums do
+        let since = trade.at - Duration::seconds(VOLUME_WINDOW_SECS);
+        while state.window.front().is_some_and(|(at, _, _)| *at < since) {
+            state.window.pop_front();
+        }
+        state.window.push_back((trade.at, trade.side, trade.quantity));
+
+        let config = self.pricing.for_item(&trade.item_key);
+        let market = state.market_state(state.report.end_multiplier, trade.at);
+        let multiplier = config.after_trade(&market, trade.side, trade.quantity);
+        let (sell_price, buy_price) = config.prices(state.item.base_price, multiplier);
+        state.set_multiplier(multiplier, sell_price, buy_price, trade.at, PriceSource::Trade);
+
+        let point = TimelinePoint {
+            hour: (trade.at - start).num_hours(),
+            trades: self.totals.trades,
+            money_supply_change: self.totals.money_supply_change,
+            fee_revenue: self.totals.fee_revenue,
+        };
+        match self.timeline.last_mut() {
+            Some(last) if last.hour == point.hour => *last = point,
+            _ => self.timeline.push(point),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// bin/moji-sim/input.rs
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use moji::{
+    fees::{FeeOperation, FeeSchedule},
+    pricing::{PricingConfig, PricingTable, Side},
+};
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+fn default_min_multiplier() -> f64 {
+    0.1
+}
+
+fn default_max_multiplier() -> f64 {
+    4.0
+}
+
+/// Starting state of an item. A `tb_market_items` export works as-is.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimItem {
+    pub item_key: String,
+    pub base_price: i64,
+    #[serde(default = "default_multiplier")]
+    pub price_multiplier: f64,
+    #[serde(default = "default_min_multiplier")]
+    pub min_multiplier: f64,
+    #[serde(default = "default_max_multiplier")]
+    pub max_multiplier: f64,
+    #[serde(default)]
+    pub regeneration_decay: Option<f64>,
+}
+
+/// One row of a `tb_market_transactions` export. Other columns are ignored.
+#[derive(Debug, Deserialize)]
+struct TransactionRecord {
+    item_key: String,
+    transaction_type: String,
+    quantity: i32,
+    timestamp: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SimTrade {
+    pub at: DateTime<Utc>,
+    pub item_key: String,
+    pub side: Side,
+    pub quantity: i32,
+}
+
+/// Synthetic player behaviour: each actor repeats the same trade on a fixed
+/// schedule for the length of the run.
+#[derive(Debug, Deserialize)]
+pub struct Script {
+    pub hours: i64,
+    pub actors: Vec<Actor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Actor {
+    pub item_key: String,
+    pub side: Side,
+    pub quantity: i32,
+    pub every_minutes: i64,
+    #[serde(default)]
+    pub start_minute: i64,
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// Reads a list of rows from a `.csv` file, or a JSON array otherwise.
+fn read_rows<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    let data = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if is_csv(path) {
+        csv::Reader::from_reader(data.as_slice())
+            .deserialize()
+            .enumerate()
+            .map(|(line, row)| row.map_err(|e| format!("{} row {}: {}", path.display(), line + 1, e)))
+            .collect()
+    } else {
+        serde_json::from_slice(&data).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let data = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_slice(&data).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|at| at.and_utc())
+        .map_err(|_| format!("Invalid timestamp: {}", value))
+}
+
+pub fn load_items(path: &Path) -> Result<Vec<SimItem>, String> {
+    read_rows(path)
+}
+
+/// Replays an export in timestamp order.
+pub fn load_transactions(path: &Path) -> Result<Vec<SimTrade>, String> {
+    let records: Vec<TransactionRecord> = read_rows(path)?;
+
+    let mut trades = records
+        .into_iter()
+        .map(|r| {
+            let side = match r.transaction_type.as_str() {
+                "SELL" => Side::Sell,
+                "BUY" => Side::Buy,
+                other => return Err(format!("Unknown transaction type: {}", other)),
+            };
+            Ok(SimTrade {
+                at: parse_timestamp(&r.timestamp)?,
+                item_key: r.item_key,
+                side,
+                quantity: r.quantity,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    trades.sort_by_key(|t| t.at);
+    Ok(trades)
+}
+
+/// Expands a script into trades starting at the unix epoch.
+pub fn load_script(path: &Path) -> Result<Vec<SimTrade>, String> {
+    let script: Script = read_json(path)?;
+    if script.hours <= 0 {
+        return Err("Script hours must be greater than 0".to_string());
+    }
+
+    let start = DateTime::<Utc>::UNIX_EPOCH;
+    let end = start + Duration::hours(script.hours);
+    let mut trades = Vec::new();
+
+    for actor in &script.actors {
+        if actor.every_minutes <= 0 || actor.quantity <= 0 {
+            return Err(format!("{}: every_minutes and quantity must be greater than 0", actor.item_key));
+        }
+        let mut at = start + Duration::minutes(actor.start_minute);
+        while at < end {
+            trades.push(SimTrade {
+                at,
+                item_key: actor.item_key.clone(),
+                side: actor.side,
+                quantity: actor.quantity,
+            });
+            at += Duration::minutes(actor.every_minutes);
+        }
+    }
+
+    trades.sort_by_key(|t| t.at);
+    Ok(trades)
+}
+
+/// `tb_config`-style overrides, e.g. `{ "market_vat_rate": 0.2 }`.
+pub fn load_config_overrides(path: &Path) -> Result<HashMap<String, f64>, String> {
+    read_json(path)
+}
+
+/// Models keyed like `tb_pricing_models.scope`, with `*` as the global one.
+pub fn load_pricing(path: &Path) -> Result<PricingTable, String> {
+    let models: HashMap<String, PricingConfig> = read_json(path)?;
+
+    let mut table = PricingTable::default();
+    for (scope, config) in models {
+        config.validate().map_err(|e| format!("Pricing model for {}: {}", scope, e))?;
+        table.insert(scope, config);
+    }
+    Ok(table)
+}
+
+/// Schedules keyed like `tb_fee_schedules.operation`, e.g. `market_sell`.
+pub fn load_fee_schedules(path: &Path) -> Result<HashMap<FeeOperation, FeeSchedule>, String> {
+    let schedules: HashMap<FeeOperation, FeeSchedule> = read_json(path)?;
+
+    for (operation, schedule) in &schedules {
+        schedule.validate().map_err(|e| format!("Fee schedule for {}: {}", operation, e))?;
+    }
+    Ok(schedules)
+}
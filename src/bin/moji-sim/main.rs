@@ -0,0 +1,102 @@
+// bin/moji-sim/main.rs
+//! Replays exported trades, or a synthetic script, against an in-memory
+//! market and prints a JSON report of prices, money supply and fees.
+mod engine;
+mod input;
+
+use std::{collections::HashMap, fs, path::PathBuf, process};
+
+use moji::{api::ConfigManager, pricing::PricingTable};
+
+use crate::engine::Simulation;
+
+const USAGE: &str = "Usage: moji-sim --items <items.csv|json> (--trades <trades.csv|json> | --script <script.json>)
+               [--config <rates.json>] [--pricing <models.json>] [--fees <schedules.json>]
+               [--output <report.json>]";
+
+#[derive(Default)]
+struct Args {
+    items: Option<PathBuf>,
+    trades: Option<PathBuf>,
+    script: Option<PathBuf>,
+    config: Option<PathBuf>,
+    pricing: Option<PathBuf>,
+    fees: Option<PathBuf>,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut argv = std::env::args().skip(1);
+
+    while let Some(flag) = argv.next() {
+        if flag == "-h" || flag == "--help" {
+            println!("{}", USAGE);
+            process::exit(0);
+        }
+        let value = argv.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+        match flag.as_str() {
+            "--items" => args.items = Some(value.into()),
+            "--trades" => args.trades = Some(value.into()),
+            "--script" => args.script = Some(value.into()),
+            "--config" => args.config = Some(value.into()),
+            "--pricing" => args.pricing = Some(value.into()),
+            "--fees" => args.fees = Some(value.into()),
+            "--output" => args.output = Some(value.into()),
+            _ => return Err(format!("Unknown option {}", flag)),
+        }
+    }
+
+    Ok(args)
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let items = input::load_items(args.items.as_deref().ok_or("--items is required")?)?;
+    let trades = match (&args.trades, &args.script) {
+        (Some(path), None) => input::load_transactions(path)?,
+        (None, Some(path)) => input::load_script(path)?,
+        _ => return Err("Pass exactly one of --trades or --script".to_string()),
+    };
+
+    let overrides = match &args.config {
+        Some(path) => input::load_config_overrides(path)?,
+        None => HashMap::new(),
+    };
+    let config = ConfigManager::from_map(&overrides).map_err(|e| format!("--config: {}", e))?;
+    let pricing = match &args.pricing {
+        Some(path) => input::load_pricing(path)?,
+        None => PricingTable::default(),
+    };
+    let fees = match &args.fees {
+        Some(path) => input::load_fee_schedules(path)?,
+        None => HashMap::new(),
+    };
+
+    let report = Simulation::new(config, pricing, fees, items).run(&trades);
+
+    eprintln!(
+        "Simulated {} trades ({} skipped), {} regeneration ticks: money supply {:+}, fee revenue {}",
+        report.totals.trades,
+        report.totals.skipped_trades,
+        report.totals.regeneration_ticks,
+        report.totals.money_supply_change,
+        report.totals.fee_revenue
+    );
+
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    match &args.output {
+        Some(path) => fs::write(path, json).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let result = parse_args().and_then(run);
+    if let Err(e) = result {
+        eprintln!("moji-sim: {}\n\n{}", e, USAGE);
+        process::exit(1);
+    }
+}
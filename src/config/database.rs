@@ -1,19 +1,34 @@
-use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
-use std::env;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    mysql::{MySqlPool, MySqlPoolOptions},
+};
 use std::time::Duration;
 
+use crate::config::settings::DatabaseSettings;
+
+/// Schema migrations from `migrations/`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
 /// Creates and returns a MySQL connection pool
-pub async fn create_pool() -> Result<MySqlPool, sqlx::Error> {
-    let database_url = env::var("DATABASE_URL")
-        .expect("DATABASE_URL environment variable must be set");
+pub async fn create_pool(settings: &DatabaseSettings) -> Result<MySqlPool, sqlx::Error> {
+    // Settings::load refuses to start without a URL, this only guards direct callers
+    let database_url = settings
+        .url
+        .as_deref()
+        .ok_or_else(|| sqlx::Error::Configuration("no database URL configured".into()))?;
 
-    // Configure connection pool with reasonable defaults
     MySqlPoolOptions::new()
-        .max_connections(10)
-        .min_connections(1)
-        .max_lifetime(Duration::from_secs(1800)) // 30 minutes
-        .acquire_timeout(Duration::from_secs(30))
-        .idle_timeout(Duration::from_secs(600)) // 10 minutes
-        .connect(&database_url)
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .max_lifetime(Duration::from_secs(settings.max_lifetime_secs))
+        .acquire_timeout(Duration::from_secs(settings.acquire_timeout_secs))
+        .idle_timeout(Duration::from_secs(settings.idle_timeout_secs))
+        .connect(database_url)
         .await
-}
\ No newline at end of file
+}
+
+/// Applies any pending migrations. Already applied ones are skipped, and a
+/// migration that was edited after being applied is reported as an error.
+pub async fn run_migrations(pool: &MySqlPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await
+}
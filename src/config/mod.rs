@@ -1,3 +1,5 @@
 pub mod database;
+pub mod settings;
 
-pub use database::create_pool;
\ No newline at end of file
+pub use database::{create_pool, run_migrations};
+pub use settings::Settings;
\ No newline at end of file
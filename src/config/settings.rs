@@ -0,0 +1,189 @@
+use std::{env, fmt, fs, net::SocketAddr, path::Path, str::FromStr};
+
+use axum::http::HeaderValue;
+use serde::Deserialize;
+
+/// File read when `MOJI_CONFIG` isn't set. It's optional, the defaults and
+/// environment variables are enough to start a dev server.
+const DEFAULT_SETTINGS_PATH: &str = "moji.toml";
+
+/// Startup settings: `moji.toml` (or the file named by `MOJI_CONFIG`), then
+/// environment overrides on top.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub server: ServerSettings,
+    pub database: DatabaseSettings,
+    pub cors: CorsSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub bind: SocketAddr,
+    /// How long background services get to finish their current run on shutdown.
+    pub shutdown_grace_secs: u64,
+    /// Key that signs market quotes. Without one a random key is used, so
+    /// quotes don't survive a restart or work across instances.
+    pub quote_secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    pub url: Option<String>,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub max_lifetime_secs: u64,
+    pub acquire_timeout_secs: u64,
+    pub idle_timeout_secs: u64,
+    /// Apply pending migrations on startup. Turn off to run `moji migrate` by hand.
+    pub auto_migrate: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsSettings {
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 9696)),
+            shutdown_grace_secs: 30,
+            quote_secret: None,
+        }
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            url: None,
+            max_connections: 10,
+            min_connections: 1,
+            max_lifetime_secs: 1800, // 30 minutes
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600, // 10 minutes
+            auto_migrate: true,
+        }
+    }
+}
+
+impl Default for CorsSettings {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![
+                "http://localhost:9696".to_string(),
+                "http://127.0.0.1:9696".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SettingsError(String);
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+fn env_override<T: FromStr>(name: &str, target: &mut T) -> Result<(), SettingsError> {
+    if let Ok(value) = env::var(name) {
+        *target = value
+            .parse()
+            .map_err(|_| SettingsError(format!("{} has an invalid value: {}", name, value)))?;
+    }
+    Ok(())
+}
+
+impl Settings {
+    pub fn load() -> Result<Self, SettingsError> {
+        let mut settings = match env::var("MOJI_CONFIG") {
+            Ok(path) => Self::from_file(Path::new(&path))?,
+            Err(_) if Path::new(DEFAULT_SETTINGS_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_SETTINGS_PATH))?
+            }
+            Err(_) => Self::default(),
+        };
+
+        settings.apply_env()?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    fn from_file(path: &Path) -> Result<Self, SettingsError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| SettingsError(format!("Failed to read {}: {}", path.display(), e)))?;
+        toml::from_str(&contents).map_err(|e| SettingsError(format!("Invalid {}: {}", path.display(), e)))
+    }
+
+    fn apply_env(&mut self) -> Result<(), SettingsError> {
+        env_override("MOJI_BIND", &mut self.server.bind)?;
+        env_override("MOJI_SHUTDOWN_GRACE_SECS", &mut self.server.shutdown_grace_secs)?;
+        env_override("MOJI_DB_MAX_CONNECTIONS", &mut self.database.max_connections)?;
+        env_override("MOJI_DB_MIN_CONNECTIONS", &mut self.database.min_connections)?;
+        env_override("MOJI_DB_MAX_LIFETIME_SECS", &mut self.database.max_lifetime_secs)?;
+        env_override("MOJI_DB_ACQUIRE_TIMEOUT_SECS", &mut self.database.acquire_timeout_secs)?;
+        env_override("MOJI_DB_IDLE_TIMEOUT_SECS", &mut self.database.idle_timeout_secs)?;
+        env_override("MOJI_DB_AUTO_MIGRATE", &mut self.database.auto_migrate)?;
+
+        // DATABASE_URL is also what the sqlx macros read, so keep honouring it
+        if let Ok(url) = env::var("DATABASE_URL") {
+            self.database.url = Some(url);
+        }
+        if let Ok(secret) = env::var("MOJI_QUOTE_SECRET") {
+            self.server.quote_secret = Some(secret);
+        }
+        if let Ok(origins) = env::var("MOJI_CORS_ORIGINS") {
+            self.cors.allowed_origins = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), SettingsError> {
+        if self.database.url.as_deref().is_none_or(str::is_empty) {
+            return Err(SettingsError(
+                "No database URL, set DATABASE_URL or database.url".to_string(),
+            ));
+        }
+        if self.database.max_connections == 0 {
+            return Err(SettingsError("database.max_connections must be at least 1".to_string()));
+        }
+        if self.database.min_connections > self.database.max_connections {
+            return Err(SettingsError(
+                "database.min_connections cannot exceed database.max_connections".to_string(),
+            ));
+        }
+        if self.database.acquire_timeout_secs == 0 {
+            return Err(SettingsError("database.acquire_timeout_secs must be at least 1".to_string()));
+        }
+        if self.server.quote_secret.as_deref().is_some_and(|secret| secret.len() < 32) {
+            return Err(SettingsError("server.quote_secret must be at least 32 characters".to_string()));
+        }
+        self.cors_origins()?;
+        Ok(())
+    }
+
+    /// CORS origins as header values, checked during `load`.
+    pub fn cors_origins(&self) -> Result<Vec<HeaderValue>, SettingsError> {
+        self.cors
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin)
+                    .map_err(|_| SettingsError(format!("Invalid CORS origin: {}", origin)))
+            })
+            .collect()
+    }
+}
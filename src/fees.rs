@@ -0,0 +1,286 @@
+// fees.rs
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::money::{Money, MoneyOverflow, Rate, Rounding, BPS_PER_UNIT};
+
+/// Fees round down, so a player is never charged a fraction of a coin.
+pub const FEE_ROUNDING: Rounding = Rounding::Floor;
+
+/// Every operation that charges a fee, each with its own schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeOperation {
+    WalletToBank,
+    BankToWallet,
+    MarketSell,
+    MarketBuy,
+    P2p,
+}
+
+impl FeeOperation {
+    pub const ALL: [FeeOperation; 5] = [
+        FeeOperation::WalletToBank,
+        FeeOperation::BankToWallet,
+        FeeOperation::MarketSell,
+        FeeOperation::MarketBuy,
+        FeeOperation::P2p,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|operation| operation.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// The operation for a transfer between two of a player's accounts.
+    pub fn transfer(from: &str, to: &str) -> Option<Self> {
+        match (from, to) {
+            ("wallet", "bank") => Some(FeeOperation::WalletToBank),
+            ("bank", "wallet") => Some(FeeOperation::BankToWallet),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeOperation::WalletToBank => "wallet_to_bank",
+            FeeOperation::BankToWallet => "bank_to_wallet",
+            FeeOperation::MarketSell => "market_sell",
+            FeeOperation::MarketBuy => "market_buy",
+            FeeOperation::P2p => "p2p",
+        }
+    }
+}
+
+impl fmt::Display for FeeOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a schedule's brackets combine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BracketMode {
+    /// The bracket the amount falls in prices the whole amount.
+    #[default]
+    Tiered,
+    /// Each bracket's rate applies only to the part of the amount inside it,
+    /// like income tax. The flat fee is the one of the highest bracket reached.
+    Marginal,
+}
+
+/// A bracket starting at `from` coins (inclusive) and running up to the next one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeBracket {
+    pub from: Money,
+    #[serde(default)]
+    pub flat: Money,
+    #[serde(default)]
+    pub rate: Rate,
+}
+
+/// The fee charged on an amount, stored per operation in `tb_fee_schedules`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeSchedule {
+    pub brackets: Vec<FeeBracket>,
+    #[serde(default)]
+    pub mode: BracketMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_fee: Option<Money>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<Money>,
+}
+
+impl FeeSchedule {
+    /// A single percentage on every amount.
+    pub fn flat_rate(rate: Rate) -> Self {
+        Self {
+            brackets: vec![FeeBracket { from: Money::ZERO, flat: Money::ZERO, rate }],
+            mode: BracketMode::Tiered,
+            min_fee: None,
+            max_fee: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let Some(first) = self.brackets.first() else {
+            return Err("A fee schedule needs at least one bracket".to_string());
+        };
+        if first.from != Money::ZERO {
+            return Err("The first bracket must start from 0".to_string());
+        }
+        if self.brackets.windows(2).any(|pair| pair[0].from >= pair[1].from) {
+            return Err("Brackets must be in increasing order of from".to_string());
+        }
+        for bracket in &self.brackets {
+            if bracket.flat < Money::ZERO {
+                return Err(format!("Bracket from {}: flat fee cannot be negative", bracket.from));
+            }
+            if bracket.rate > Rate::from_bps(BPS_PER_UNIT) {
+                return Err(format!("Bracket from {}: rate cannot be above 100%", bracket.from));
+            }
+        }
+        if self.min_fee.is_some_and(|min| min < Money::ZERO) || self.max_fee.is_some_and(|max| max < Money::ZERO) {
+            return Err("min_fee and max_fee cannot be negative".to_string());
+        }
+        if self.min_fee.zip(self.max_fee).is_some_and(|(min, max)| min > max) {
+            return Err("min_fee cannot be above max_fee".to_string());
+        }
+        Ok(())
+    }
+
+    /// The fee on `amount`, clamped to `min_fee`/`max_fee`. Each rate rounds
+    /// down on its own, so a marginal schedule never rounds up across brackets.
+    pub fn fee(&self, amount: Money) -> Result<Money, MoneyOverflow> {
+        let Some(bracket) = self.brackets.iter().rev().find(|b| b.from <= amount).or(self.brackets.first()) else {
+            return Ok(Money::ZERO);
+        };
+
+        let variable = match self.mode {
+            BracketMode::Tiered => bracket.rate.apply(amount, FEE_ROUNDING)?,
+            BracketMode::Marginal => {
+                let mut total = Money::ZERO;
+                for (i, b) in self.brackets.iter().enumerate() {
+                    if amount <= b.from {
+                        break;
+                    }
+                    let upper = self.brackets.get(i + 1).map_or(amount, |next| next.from.min(amount));
+                    total = total.checked_add(b.rate.apply(upper.checked_sub(b.from)?, FEE_ROUNDING)?)?;
+                }
+                total
+            }
+        };
+
+        let mut fee = bracket.flat.checked_add(variable)?;
+        if let Some(min) = self.min_fee {
+            fee = fee.max(min);
+        }
+        if let Some(max) = self.max_fee {
+            fee = fee.min(max);
+        }
+        Ok(fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::api::ConfigManager;
+
+    fn bracket(from: i64, flat: i64, bps: u32) -> FeeBracket {
+        FeeBracket { from: Money::new(from), flat: Money::new(flat), rate: Rate::from_bps(bps) }
+    }
+
+    fn schedule(mode: BracketMode, brackets: Vec<FeeBracket>) -> FeeSchedule {
+        FeeSchedule { brackets, mode, min_fee: None, max_fee: None }
+    }
+
+    fn fee(schedule: &FeeSchedule, amount: i64) -> i64 {
+        schedule.fee(Money::new(amount)).unwrap().amount()
+    }
+
+    #[test]
+    fn transfer_picks_the_schedule_for_its_direction() {
+        assert_eq!(FeeOperation::transfer("wallet", "bank"), Some(FeeOperation::WalletToBank));
+        assert_eq!(FeeOperation::transfer("bank", "wallet"), Some(FeeOperation::BankToWallet));
+        assert_eq!(FeeOperation::transfer("wallet", "wallet"), None);
+        assert_eq!(FeeOperation::transfer("bank", "market"), None);
+    }
+
+    #[test]
+    fn tiered_bracket_starts_at_its_from() {
+        let tiered = schedule(BracketMode::Tiered, vec![bracket(0, 0, 1000), bracket(10_000, 0, 500)]);
+        assert_eq!(fee(&tiered, 0), 0);
+        assert_eq!(fee(&tiered, 9_999), 999);
+        assert_eq!(fee(&tiered, 10_000), 500);
+        assert_eq!(fee(&tiered, 20_000), 1_000);
+    }
+
+    #[test]
+    fn tiered_adds_the_flat_fee_of_its_bracket() {
+        let tiered = schedule(BracketMode::Tiered, vec![bracket(0, 5, 1000), bracket(1_000, 10, 500)]);
+        assert_eq!(fee(&tiered, 999), 5 + 99);
+        assert_eq!(fee(&tiered, 1_000), 10 + 50);
+    }
+
+    #[test]
+    fn marginal_rates_apply_to_the_part_in_each_bracket() {
+        let marginal = schedule(BracketMode::Marginal, vec![bracket(0, 5, 1000), bracket(1_000, 10, 500)]);
+        assert_eq!(fee(&marginal, 500), 5 + 50);
+        assert_eq!(fee(&marginal, 1_000), 10 + 100);
+        assert_eq!(fee(&marginal, 3_000), 10 + 100 + 100);
+        // Each bracket rounds down on its own: 5% of 19 is 0
+        assert_eq!(fee(&marginal, 1_019), 10 + 100);
+    }
+
+    #[test]
+    fn caps_clamp_the_fee() {
+        let mut capped = schedule(BracketMode::Marginal, vec![bracket(0, 5, 1000), bracket(1_000, 10, 500)]);
+        capped.min_fee = Some(Money::new(8));
+        capped.max_fee = Some(Money::new(200));
+        assert_eq!(fee(&capped, 10), 8);
+        assert_eq!(fee(&capped, 500), 55);
+        assert_eq!(fee(&capped, 3_000), 200);
+        assert_eq!(fee(&capped, 0), 8);
+    }
+
+    #[test]
+    fn fee_reports_overflow() {
+        let huge = schedule(BracketMode::Tiered, vec![bracket(0, i64::MAX, 1)]);
+        assert_eq!(huge.fee(Money::new(10_000)), Err(MoneyOverflow));
+    }
+
+    #[test]
+    fn validate_rejects_malformed_schedules() {
+        assert!(schedule(BracketMode::Tiered, vec![]).validate().is_err());
+        assert!(schedule(BracketMode::Tiered, vec![bracket(1, 0, 100)]).validate().is_err());
+        assert!(schedule(BracketMode::Tiered, vec![bracket(0, 0, 100), bracket(0, 0, 50)]).validate().is_err());
+        assert!(schedule(BracketMode::Tiered, vec![bracket(0, -1, 100)]).validate().is_err());
+        assert!(schedule(BracketMode::Tiered, vec![bracket(0, 0, BPS_PER_UNIT + 1)]).validate().is_err());
+
+        let mut inverted = FeeSchedule::flat_rate(Rate::from_bps(100));
+        inverted.min_fee = Some(Money::new(10));
+        inverted.max_fee = Some(Money::new(5));
+        assert!(inverted.validate().is_err());
+
+        assert!(schedule(BracketMode::Marginal, vec![bracket(0, 0, 100), bracket(500, 1, 50)])
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn schedule_json_defaults_to_tiered() {
+        let parsed: FeeSchedule = serde_json::from_str(r#"{ "brackets": [{ "from": 0, "rate": 0.02 }] }"#).unwrap();
+        assert_eq!(parsed, FeeSchedule::flat_rate(Rate::from_bps(200)));
+        assert!(serde_json::from_str::<FeeSchedule>(r#"{ "brackets": [], "cap": 5 }"#).is_err());
+    }
+
+    #[test]
+    fn default_schedules_follow_tb_config() {
+        let config = ConfigManager::from_map(&HashMap::new()).unwrap();
+        let default = |operation| config.default_fee_schedule(operation);
+
+        assert_eq!(fee(&default(FeeOperation::WalletToBank), 9_999), 999);
+        assert_eq!(fee(&default(FeeOperation::WalletToBank), 10_000), 500);
+        assert_eq!(fee(&default(FeeOperation::BankToWallet), 10_000), 1_000);
+        assert_eq!(fee(&default(FeeOperation::MarketSell), 1_000), 20);
+        assert_eq!(fee(&default(FeeOperation::MarketBuy), 1_000), 20);
+        assert_eq!(fee(&default(FeeOperation::P2p), 1_000), 50);
+        for operation in FeeOperation::ALL {
+            assert!(default(operation).validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn zero_threshold_charges_the_wallet_to_bank_rate_on_everything() {
+        let config = ConfigManager::from_map(&HashMap::from([("wallet_to_bank_threshold".to_string(), 0.0)])).unwrap();
+        let schedule = config.default_fee_schedule(FeeOperation::WalletToBank);
+        assert_eq!(schedule.brackets.len(), 1);
+        assert_eq!(fee(&schedule, 100), 5);
+    }
+}
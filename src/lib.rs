@@ -0,0 +1,19 @@
+pub mod api;
+pub mod config;
+pub mod fees;
+pub mod money;
+pub mod pricing;
+pub mod services;
+
+use sqlx::MySqlPool;
+
+use crate::api::{auth::KeyUsage, config::ConfigHandle, leaderboard::LeaderboardCache, quotes::QuoteSigner};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub config: ConfigHandle,
+    pub leaderboards: LeaderboardCache,
+    pub quotes: QuoteSigner,
+    pub key_usage: KeyUsage,
+}
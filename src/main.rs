@@ -1,31 +1,66 @@
-mod api;
-mod config;
-mod services;
+use std::{net::SocketAddr, sync::Arc};
 
-use std::net::SocketAddr;
-
-use api::user::{create_user, get_user};
 use axum::{
     Router,
     http::{HeaderName, Method},
-    routing::{get, post},
+    middleware,
+    routing::{delete, get, post, put},
 };
-use config::create_pool;
-use sqlx::MySqlPool;
+use tokio::time::Duration;
 use tower_http::cors::CorsLayer;
 
-use crate::{
+use moji::{
+    AppState,
     api::{
-        market::{get_market_item_endpoint, get_market_items, get_market_items_light, sell_item}, user::{get_user_bank, get_user_wallet, transfer_money}, ConfigManager
+        auth::{
+            authenticate, bootstrap_admin_key, create_api_key, list_api_keys, require_admin,
+            require_player_ops, require_read, revoke_api_key, KeyUsage,
+        },
+        bank::{close_bank, get_bank_session, open_bank},
+        fees::{delete_fee_schedule, get_fee_quote, list_fee_schedules, set_fee_schedule},
+        idempotency::idempotency,
+        leaderboard::{
+            exclude_from_leaderboards, get_items_leaderboard, get_sellers_leaderboard,
+            get_wealth_leaderboard, include_in_leaderboards, list_leaderboard_exclusions,
+            LeaderboardCache,
+        },
+        ledger::{backfill_opening_balances, get_user_ledger, get_user_ledger_audit},
+        market_admin::{
+            create_market_item, delete_market_item, disable_market_item, enable_market_item,
+            import_catalogue, list_catalogue, update_market_item,
+        },
+        price_history::get_price_history,
+        pricing::{delete_pricing_model, list_pricing_models, set_pricing_model},
+        quotes::{create_quote, QuoteSigner},
+        regeneration::{
+            get_regeneration_status, list_regeneration_runs, pause_regeneration, resume_regeneration,
+            set_item_decay, trigger_regeneration,
+        },
+        transactions::get_user_transactions,
+        market::{
+            buy_item, get_market_item_endpoint, get_market_items, get_market_items_light, sell_item,
+            sell_items_batch,
+        },
+        user::{
+            create_user, get_user, get_user_bank, get_user_by_name_endpoint, get_user_names,
+            get_user_wallet, login_user, pay_player, search_name_history, transfer_money,
+        },
+        config::{
+            get_config, get_config_changes, list_config, reload_config, update_config, ConfigHandle,
+        },
+        ConfigManager
+    },
+    config::{create_pool, run_migrations, Settings},
+    services::{
+        bank_session_expiry::BankSessionExpiryService, config_refresh::ConfigRefreshService,
+        idempotency_cleanup::IdempotencyCleanupService,
+        price_regeneration::PriceRegenerationService,
+        signature_cleanup::SignatureCleanupService,
+        supervisor::{shutdown_signal, Supervisor},
     },
-    services::price_regeneration::PriceRegenerationService,
 };
 
-#[derive(Clone)]
-pub struct AppState {
-    pub pool: MySqlPool,
-    pub config: ConfigManager,
-}
+const USAGE: &str = "Usage: moji [migrate]";
 
 #[tokio::main]
 async fn main() {
@@ -35,7 +70,25 @@ async fn main() {
         .with_max_level(tracing::Level::INFO)
         .init();
 
-    let db_pool = match create_pool().await {
+    // `moji migrate` applies pending migrations and exits, no argument starts the server
+    let migrate_only = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("migrate") => true,
+        Some(other) => {
+            eprintln!("Unknown command {}\n{}", other, USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::error!("Invalid settings: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let db_pool = match create_pool(&settings.database).await {
         Ok(pool) => pool,
         Err(e) => {
             tracing::error!("Failed to connect to database: {}", e);
@@ -44,58 +97,212 @@ async fn main() {
     };
     tracing::info!("✅ Successfully connected to database");
 
+    if migrate_only || settings.database.auto_migrate {
+        if let Err(e) = run_migrations(&db_pool).await {
+            tracing::error!("Failed to apply migrations: {}", e);
+            std::process::exit(1);
+        }
+        tracing::info!("✅ Database schema is up to date");
+    }
+    if migrate_only {
+        db_pool.close().await;
+        return;
+    }
+
+    // Already validated by Settings::load
+    let origins = settings.cors_origins().unwrap_or_default();
     let cors = CorsLayer::new()
-        .allow_origin([
-            "http://localhost:9696".parse().unwrap(),
-            "http://127.0.0.1:9696".parse().unwrap(),
-        ])
+        .allow_origin(origins)
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers([
             HeaderName::from_static("content-type"),
             HeaderName::from_static("authorization"),
+            HeaderName::from_static("idempotency-key"),
         ])
         .allow_credentials(true);
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 9696));
+    let addr = settings.server.bind;
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
     tracing::info!("🚀 Server running at http://{}", addr);
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
 
-    let config = ConfigManager::load_from_db(&db_pool).await.unwrap();
+    let config = match ConfigManager::load_from_db(&db_pool).await {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("Failed to load tb_config: {}", e);
+            std::process::exit(1);
+        }
+    };
     tracing::info!(
-        "✅ Configuration loaded: VAT={}%, Transfer={}%, WalletToBank={}%, Threshold={}, MarketFee={}%",
-        config.market_vat_rate * 100.0,
-        config.transfer_fee_rate * 100.0,
-        config.wallet_to_bank_fee_rate * 100.0,
+        "✅ Configuration loaded: VAT={}, Transfer={}, WalletToBank={}, Threshold={}, MarketFee={}, P2P={}",
+        config.market_vat_rate,
+        config.transfer_fee_rate,
+        config.wallet_to_bank_fee_rate,
         config.wallet_to_bank_threshold,
-        config.market_transaction_fee * 100.0
+        config.market_transaction_fee,
+        config.p2p_fee_rate
     );
 
-    let regen_service = PriceRegenerationService::new(db_pool.clone());
-    tokio::spawn(async move {
-        regen_service.start().await;
+    match backfill_opening_balances(&db_pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("📒 Posted opening ledger balances for {} players", count),
+        Err(e) => {
+            tracing::error!("Failed to backfill opening ledger balances: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    match bootstrap_admin_key(&db_pool).await {
+        Ok(true) => tracing::info!("🔑 Bootstrap admin key registered from MOJI_ADMIN_KEY"),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!("Failed to register bootstrap admin key: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let config = ConfigHandle::new(config);
+    let mut supervisor = Supervisor::new();
+
+    let refresh_service = Arc::new(ConfigRefreshService::new(db_pool.clone(), config.clone()));
+    supervisor.spawn("Config refresh service", move |shutdown| {
+        let service = refresh_service.clone();
+        async move { service.start(shutdown).await }
+    });
+
+    let expiry_service = Arc::new(BankSessionExpiryService::new(db_pool.clone()));
+    supervisor.spawn("Bank session expiry service", move |shutdown| {
+        let service = expiry_service.clone();
+        async move { service.start(shutdown).await }
+    });
+
+    let cleanup_service = Arc::new(IdempotencyCleanupService::new(db_pool.clone()));
+    supervisor.spawn("Idempotency key cleanup service", move |shutdown| {
+        let service = cleanup_service.clone();
+        async move { service.start(shutdown).await }
+    });
+
+    let signature_service = Arc::new(SignatureCleanupService::new(db_pool.clone()));
+    supervisor.spawn("Signature cleanup service", move |shutdown| {
+        let service = signature_service.clone();
+        async move { service.start(shutdown).await }
     });
 
+    let regen_service = Arc::new(PriceRegenerationService::new(db_pool.clone(), config.clone()));
+    supervisor.spawn("Price regeneration service", move |shutdown| {
+        let service = regen_service.clone();
+        async move { service.start(shutdown).await }
+    });
+
+    let quotes = match &settings.server.quote_secret {
+        Some(secret) => QuoteSigner::new(secret.as_bytes()),
+        None => {
+            tracing::warn!("⚠️ No quote_secret set, market quotes won't survive a restart");
+            QuoteSigner::random()
+        }
+    };
+
     let app_state = AppState {
-        pool: db_pool,
+        pool: db_pool.clone(),
         config,
+        leaderboards: LeaderboardCache::new(),
+        quotes,
+        key_usage: KeyUsage::new(),
     };
-    let app = Router::new()
-        .route("/api/user", post(create_user))
+    // Reads, player-facing operations and admin tools each need their own scope
+    let read_routes = Router::new()
         .route("/api/user/{uuid}", get(get_user))
+        .route("/api/user/by-name/{name}", get(get_user_by_name_endpoint))
         .route("/api/user/{uuid}/wallet", get(get_user_wallet))
         .route("/api/user/{uuid}/bank", get(get_user_bank))
-        .route("/api/user/{uuid}/transfer", post(transfer_money))
-        .route("/api/market/sell/{uuid}", post(sell_item))
+        .route("/api/user/{uuid}/ledger", get(get_user_ledger))
+        .route("/api/user/{uuid}/transactions", get(get_user_transactions))
+        .route("/api/user/{uuid}/bank/session", get(get_bank_session))
         .route("/api/market/items", get(get_market_items))
         .route("/api/market/item/{key}", get(get_market_item_endpoint))
+        .route("/api/market/item/{key}/history", get(get_price_history))
         .route("/api/market/items/light", get(get_market_items_light))
+        .route("/api/leaderboards/wealth", get(get_wealth_leaderboard))
+        .route("/api/leaderboards/sellers", get(get_sellers_leaderboard))
+        .route("/api/leaderboards/items", get(get_items_leaderboard))
+        .route("/api/fees/quote", get(get_fee_quote))
+        .route_layer(middleware::from_fn(require_read));
+
+    // Everything that moves money honours Idempotency-Key, so a retry can't pay twice
+    let money_routes = Router::new()
+        .route("/api/user/{uuid}/transfer", post(transfer_money))
+        .route("/api/user/{uuid}/pay", post(pay_player))
+        .route("/api/market/sell/{uuid}", post(sell_item))
+        .route("/api/market/sell/{uuid}/batch", post(sell_items_batch))
+        .route("/api/market/buy/{uuid}", post(buy_item))
+        .route_layer(middleware::from_fn_with_state(app_state.clone(), idempotency));
+
+    let player_routes = Router::new()
+        .route("/api/user", post(create_user))
+        .route("/api/user/login", post(login_user))
+        .route("/api/user/{uuid}/bank/open", post(open_bank))
+        .route("/api/user/{uuid}/bank/close", post(close_bank))
+        .route("/api/market/quote", post(create_quote))
+        .merge(money_routes)
+        .route_layer(middleware::from_fn(require_player_ops));
+
+    let admin_routes = Router::new()
+        .route("/api/user/{uuid}/ledger/audit", get(get_user_ledger_audit))
+        .route("/api/user/{uuid}/names", get(get_user_names))
+        .route("/api/admin/names/{name}", get(search_name_history))
+        .route("/api/admin/leaderboards/exclusions", get(list_leaderboard_exclusions))
+        .route(
+            "/api/admin/leaderboards/exclusions/{uuid}",
+            put(exclude_from_leaderboards).delete(include_in_leaderboards),
+        )
+        .route("/api/admin/keys", get(list_api_keys).post(create_api_key))
+        .route("/api/admin/keys/{id}", delete(revoke_api_key))
+        .route("/api/admin/market/items", get(list_catalogue).post(create_market_item))
+        .route("/api/admin/market/items/import", post(import_catalogue))
+        .route("/api/admin/market/items/{key}", put(update_market_item).delete(delete_market_item))
+        .route("/api/admin/market/items/{key}/disable", post(disable_market_item))
+        .route("/api/admin/market/items/{key}/enable", post(enable_market_item))
+        .route("/api/admin/pricing", get(list_pricing_models))
+        .route("/api/admin/pricing/{scope}", put(set_pricing_model).delete(delete_pricing_model))
+        .route("/api/admin/fees", get(list_fee_schedules))
+        .route("/api/admin/fees/{operation}", put(set_fee_schedule).delete(delete_fee_schedule))
+        .route("/api/admin/regeneration", get(get_regeneration_status))
+        .route("/api/admin/regeneration/runs", get(list_regeneration_runs))
+        .route("/api/admin/regeneration/run", post(trigger_regeneration))
+        .route("/api/admin/regeneration/pause", post(pause_regeneration))
+        .route("/api/admin/regeneration/resume", post(resume_regeneration))
+        .route("/api/admin/regeneration/items/{key}", put(set_item_decay))
+        .route("/api/admin/config", get(list_config))
+        .route("/api/admin/config/reload", post(reload_config))
+        .route("/api/admin/config/{key}", get(get_config).put(update_config))
+        .route("/api/admin/config/{key}/history", get(get_config_changes))
+        .route_layer(middleware::from_fn(require_admin));
+
+    let app = Router::new()
+        .merge(read_routes)
+        .merge(player_routes)
+        .merge(admin_routes)
+        .layer(middleware::from_fn_with_state(app_state.clone(), authenticate))
         .with_state(app_state)
         .layer(cors);
 
-    axum::serve(
+    // Stops accepting connections on SIGTERM/Ctrl+C and waits for in-flight requests
+    let served = axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
-    .await
-    .unwrap();
+    .with_graceful_shutdown(shutdown_signal())
+    .await;
+    if let Err(e) = served {
+        tracing::error!("Server error: {}", e);
+    }
+
+    supervisor.stop(Duration::from_secs(settings.server.shutdown_grace_secs)).await;
+    db_pool.close().await;
+    tracing::info!("👋 Server stopped");
 }
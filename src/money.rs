@@ -0,0 +1,224 @@
+// money.rs
+use std::fmt;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Basis points in 100%.
+pub const BPS_PER_UNIT: u32 = 10_000;
+
+/// An amount of whole coins. Arithmetic is checked, an overflow is an error
+/// rather than a wrapped or saturated balance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+/// A percentage in basis points, 1 bp = 0.01%. Serialized as a fraction
+/// (`0.34` for 34%) like the values stored in `tb_config`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rate(u32);
+
+/// How a rate applied to an amount drops the fractional coin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round toward negative infinity.
+    Floor,
+    /// Round to the nearest coin, ties to the even one.
+    HalfEven,
+}
+
+/// An amount didn't fit in an `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneyOverflow;
+
+impl fmt::Display for MoneyOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("amount is too large")
+    }
+}
+
+impl std::error::Error for MoneyOverflow {}
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn new(amount: i64) -> Self {
+        Money(amount)
+    }
+
+    pub const fn amount(self) -> i64 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyOverflow> {
+        self.0.checked_add(other.0).map(Money).ok_or(MoneyOverflow)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyOverflow> {
+        self.0.checked_sub(other.0).map(Money).ok_or(MoneyOverflow)
+    }
+
+    /// Price of `quantity` units at this unit price.
+    pub fn checked_mul(self, quantity: i64) -> Result<Money, MoneyOverflow> {
+        self.0.checked_mul(quantity).map(Money).ok_or(MoneyOverflow)
+    }
+}
+
+impl From<Money> for i64 {
+    fn from(money: Money) -> i64 {
+        money.0
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Rate {
+    pub const ZERO: Rate = Rate(0);
+
+    pub const fn from_bps(bps: u32) -> Self {
+        Rate(bps)
+    }
+
+    pub const fn bps(self) -> u32 {
+        self.0
+    }
+
+    /// Converts a fraction such as `0.34`. Rejects negative or non-finite
+    /// values and anything finer than a whole basis point.
+    pub fn from_fraction(value: f64) -> Option<Self> {
+        let bps = value * BPS_PER_UNIT as f64;
+        if !bps.is_finite() || bps < 0.0 || bps > u32::MAX as f64 {
+            return None;
+        }
+        // Allow for the float error in values like 0.0700
+        let rounded = bps.round();
+        if (bps - rounded).abs() > 1e-6 {
+            return None;
+        }
+        Some(Rate(rounded as u32))
+    }
+
+    pub fn as_fraction(self) -> f64 {
+        self.0 as f64 / BPS_PER_UNIT as f64
+    }
+
+    /// `amount` times this rate, rounded as requested. Computed in `i128`
+    /// so only a result that doesn't fit in an `i64` can fail.
+    pub fn apply(self, amount: Money, rounding: Rounding) -> Result<Money, MoneyOverflow> {
+        let numerator = amount.0 as i128 * self.0 as i128;
+        let denominator = BPS_PER_UNIT as i128;
+
+        let quotient = numerator.div_euclid(denominator);
+        let remainder = numerator.rem_euclid(denominator);
+        let result = match rounding {
+            Rounding::Floor => quotient,
+            Rounding::HalfEven => match (remainder * 2).cmp(&denominator) {
+                std::cmp::Ordering::Less => quotient,
+                std::cmp::Ordering::Greater => quotient + 1,
+                std::cmp::Ordering::Equal => quotient + (quotient & 1),
+            },
+        };
+
+        i64::try_from(result).map(Money).map_err(|_| MoneyOverflow)
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0 as f64 / 100.0)
+    }
+}
+
+impl Serialize for Rate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_fraction())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+        Rate::from_fraction(value).ok_or_else(|| {
+            de::Error::custom(format!("invalid rate {}, expected a fraction with at most 4 decimals", value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_fraction_takes_whole_basis_points() {
+        assert_eq!(Rate::from_fraction(0.0), Some(Rate::ZERO));
+        assert_eq!(Rate::from_fraction(0.0001), Some(Rate::from_bps(1)));
+        assert_eq!(Rate::from_fraction(0.34), Some(Rate::from_bps(3400)));
+        assert_eq!(Rate::from_fraction(1.0), Some(Rate::from_bps(BPS_PER_UNIT)));
+    }
+
+    #[test]
+    fn from_fraction_absorbs_float_noise() {
+        // 0.07 * 10_000 is 700.0000000000001 as an f64
+        assert_ne!(0.07 * BPS_PER_UNIT as f64, 700.0);
+        assert_eq!(Rate::from_fraction(0.07), Some(Rate::from_bps(700)));
+        assert_eq!(Rate::from_fraction(0.1 + 0.2), Some(Rate::from_bps(3000)));
+    }
+
+    #[test]
+    fn from_fraction_rejects_invalid_rates() {
+        assert_eq!(Rate::from_fraction(-0.01), None);
+        assert_eq!(Rate::from_fraction(0.00005), None);
+        assert_eq!(Rate::from_fraction(f64::NAN), None);
+        assert_eq!(Rate::from_fraction(f64::INFINITY), None);
+        assert_eq!(Rate::from_fraction(1e10), None);
+    }
+
+    #[test]
+    fn apply_floor_rounds_down() {
+        let rate = Rate::from_bps(3400);
+        assert_eq!(rate.apply(Money::new(1001), Rounding::Floor), Ok(Money::new(340)));
+        assert_eq!(rate.apply(Money::new(1002), Rounding::Floor), Ok(Money::new(340)));
+        assert_eq!(rate.apply(Money::new(-1001), Rounding::Floor), Ok(Money::new(-341)));
+        assert_eq!(Rate::ZERO.apply(Money::new(1_000_000), Rounding::Floor), Ok(Money::ZERO));
+    }
+
+    #[test]
+    fn apply_half_even_rounds_ties_to_even() {
+        let half = Rate::from_bps(5000);
+        assert_eq!(half.apply(Money::new(3), Rounding::HalfEven), Ok(Money::new(2)));
+        assert_eq!(half.apply(Money::new(5), Rounding::HalfEven), Ok(Money::new(2)));
+        assert_eq!(half.apply(Money::new(7), Rounding::HalfEven), Ok(Money::new(4)));
+        assert_eq!(half.apply(Money::new(-3), Rounding::HalfEven), Ok(Money::new(-2)));
+        assert_eq!(half.apply(Money::new(-5), Rounding::HalfEven), Ok(Money::new(-2)));
+
+        let rate = Rate::from_bps(3400);
+        assert_eq!(rate.apply(Money::new(1001), Rounding::HalfEven), Ok(Money::new(340)));
+        assert_eq!(rate.apply(Money::new(1002), Rounding::HalfEven), Ok(Money::new(341)));
+    }
+
+    #[test]
+    fn apply_overflows_only_when_the_result_does() {
+        let all = Rate::from_bps(BPS_PER_UNIT);
+        assert_eq!(all.apply(Money::new(i64::MAX), Rounding::Floor), Ok(Money::new(i64::MAX)));
+        assert_eq!(Rate::from_bps(20_000).apply(Money::new(i64::MAX), Rounding::Floor), Err(MoneyOverflow));
+        assert_eq!(Rate::from_bps(20_000).apply(Money::new(i64::MIN), Rounding::HalfEven), Err(MoneyOverflow));
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow() {
+        assert_eq!(Money::new(i64::MAX).checked_add(Money::new(1)), Err(MoneyOverflow));
+        assert_eq!(Money::new(i64::MIN).checked_sub(Money::new(1)), Err(MoneyOverflow));
+        assert_eq!(Money::new(i64::MAX / 2 + 1).checked_mul(2), Err(MoneyOverflow));
+        assert_eq!(Money::new(100).checked_mul(3), Ok(Money::new(300)));
+    }
+
+    #[test]
+    fn rate_serializes_as_a_fraction() {
+        assert_eq!(serde_json::to_string(&Rate::from_bps(700)).unwrap(), "0.07");
+        assert_eq!(serde_json::from_str::<Rate>("0.07").unwrap(), Rate::from_bps(700));
+        assert!(serde_json::from_str::<Rate>("0.00005").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+// pricing/constant_product.rs
+use serde::{Deserialize, Serialize};
+
+use super::{require_positive, MarketState, PricingModel, Side};
+
+/// Constant-product AMM (`x * y = k`) over a virtual item reserve.
+/// At multiplier 1.0 the pool holds `liquidity` units, so deeper pools move
+/// less per trade. Selling adds to the reserve, buying draws it down.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConstantProductParams {
+    pub liquidity: f64,
+}
+
+impl Default for ConstantProductParams {
+    fn default() -> Self {
+        Self { liquidity: 10_000.0 }
+    }
+}
+
+impl PricingModel for ConstantProductParams {
+    fn after_trade(&self, state: &MarketState, side: Side, quantity: i32) -> f64 {
+        // With k = liquidity^2 the price is k / x^2, so x = liquidity / sqrt(price)
+        let k = self.liquidity * self.liquidity;
+        let reserve = self.liquidity / state.multiplier.max(f64::EPSILON).sqrt();
+        let quantity = quantity as f64;
+
+        let new_reserve = match side {
+            Side::Sell => reserve + quantity,
+            // Never drain the pool completely, the clamp takes over from there
+            Side::Buy => (reserve - quantity).max(1.0),
+        };
+
+        k / (new_reserve * new_reserve)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        require_positive("liquidity", self.liquidity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::{ModelParams, PricingConfig};
+
+    fn config(liquidity: f64) -> PricingConfig {
+        PricingConfig {
+            params: ModelParams::ConstantProduct(ConstantProductParams { liquidity }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn doubling_the_reserve_quarters_the_price() {
+        let model = ConstantProductParams { liquidity: 1_000.0 };
+        let multiplier = model.after_trade(&MarketState::at(1.0), Side::Sell, 1_000);
+        assert!((multiplier - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn deeper_pools_move_less() {
+        let state = MarketState::at(1.0);
+        let shallow = ConstantProductParams { liquidity: 1_000.0 }.after_trade(&state, Side::Buy, 100);
+        let deep = ConstantProductParams { liquidity: 100_000.0 }.after_trade(&state, Side::Buy, 100);
+        assert!(shallow > deep && deep > 1.0);
+    }
+
+    #[test]
+    fn trades_are_clamped_to_the_bounds() {
+        let state = MarketState::at(1.0);
+        // Buying out the pool would price it at liquidity^2
+        assert_eq!(config(1_000.0).after_trade(&state, Side::Buy, 5_000), 4.0);
+        assert_eq!(config(1_000.0).after_trade(&state, Side::Sell, 1_000_000), 0.1);
+    }
+
+    #[test]
+    fn validate_rejects_a_non_positive_liquidity() {
+        assert!(config(1_000.0).validate().is_ok());
+        for liquidity in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(config(liquidity).validate().is_err(), "{liquidity} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,83 @@
+// pricing/elasticity.rs
+use serde::{Deserialize, Serialize};
+
+use super::{require_positive, MarketState, PricingModel, Side};
+
+/// Constant-elasticity curve: trading `reference_volume` units moves the
+/// price by a factor of `e^(1 / elasticity)`. Higher elasticity means a
+/// flatter curve.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElasticityParams {
+    pub elasticity: f64,
+    pub reference_volume: f64,
+}
+
+impl Default for ElasticityParams {
+    fn default() -> Self {
+        Self {
+            elasticity: 1.5,
+            reference_volume: 1_000.0,
+        }
+    }
+}
+
+impl PricingModel for ElasticityParams {
+    fn after_trade(&self, state: &MarketState, side: Side, quantity: i32) -> f64 {
+        let shock = quantity as f64 / (self.reference_volume * self.elasticity);
+        match side {
+            Side::Sell => state.multiplier * (-shock).exp(),
+            Side::Buy => state.multiplier * shock.exp(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        require_positive("elasticity", self.elasticity)?;
+        require_positive("reference_volume", self.reference_volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::{ModelParams, PricingConfig};
+
+    fn config(elasticity: f64, reference_volume: f64) -> PricingConfig {
+        PricingConfig {
+            params: ModelParams::Elasticity(ElasticityParams { elasticity, reference_volume }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reference_volume_times_elasticity_moves_the_price_by_e() {
+        let model = ElasticityParams { elasticity: 1.5, reference_volume: 1_000.0 };
+        let state = MarketState::at(1.0);
+
+        assert!((model.after_trade(&state, Side::Buy, 1_500) - std::f64::consts::E).abs() < 1e-12);
+        assert!((model.after_trade(&state, Side::Sell, 1_500) - 1.0 / std::f64::consts::E).abs() < 1e-12);
+    }
+
+    #[test]
+    fn selling_then_buying_the_same_amount_round_trips() {
+        let model = ElasticityParams::default();
+        let sold = model.after_trade(&MarketState::at(1.3), Side::Sell, 250);
+        let bought = model.after_trade(&MarketState::at(sold), Side::Buy, 250);
+        assert!((bought - 1.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn trades_are_clamped_to_the_bounds() {
+        let state = MarketState::at(1.0);
+        assert_eq!(config(1.5, 1_000.0).after_trade(&state, Side::Sell, 100_000), 0.1);
+        assert_eq!(config(1.5, 1_000.0).after_trade(&state, Side::Buy, 100_000), 4.0);
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_params() {
+        assert!(config(1.5, 1_000.0).validate().is_ok());
+        assert!(config(0.0, 1_000.0).validate().is_err());
+        assert!(config(1.5, -1.0).validate().is_err());
+        assert!(config(f64::NAN, 1_000.0).validate().is_err());
+    }
+}
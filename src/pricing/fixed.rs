@@ -0,0 +1,66 @@
+// pricing/fixed.rs
+use serde::{Deserialize, Serialize};
+
+use super::{require_positive, MarketState, PricingModel, Side};
+
+/// Pins an item at a fixed multiplier regardless of trading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FixedParams {
+    pub multiplier: f64,
+}
+
+impl Default for FixedParams {
+    fn default() -> Self {
+        Self { multiplier: 1.0 }
+    }
+}
+
+impl PricingModel for FixedParams {
+    fn after_trade(&self, _state: &MarketState, _side: Side, _quantity: i32) -> f64 {
+        self.multiplier
+    }
+
+    fn regenerate(&self, _state: &MarketState, _decay: f64) -> f64 {
+        self.multiplier
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        require_positive("multiplier", self.multiplier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::{ModelParams, PricingConfig};
+
+    fn config(multiplier: f64) -> PricingConfig {
+        PricingConfig {
+            params: ModelParams::Fixed(FixedParams { multiplier }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn trades_and_regeneration_keep_the_multiplier() {
+        let state = MarketState { recent_sold: 10_000, ..MarketState::at(2.0) };
+        assert_eq!(config(1.2).after_trade(&state, Side::Sell, 5_000), 1.2);
+        assert_eq!(config(1.2).after_trade(&state, Side::Buy, 5_000), 1.2);
+        assert_eq!(config(1.2).regenerate(&state, 0.5), 1.2);
+    }
+
+    #[test]
+    fn a_multiplier_outside_the_bounds_is_clamped() {
+        let state = MarketState::at(1.0);
+        assert_eq!(config(10.0).after_trade(&state, Side::Buy, 1), 4.0);
+        assert_eq!(config(0.01).regenerate(&state, 0.5), 0.1);
+    }
+
+    #[test]
+    fn validate_rejects_a_non_positive_multiplier() {
+        assert!(config(1.0).validate().is_ok());
+        assert!(config(0.0).validate().is_err());
+        assert!(config(-1.0).validate().is_err());
+    }
+}
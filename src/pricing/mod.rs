@@ -0,0 +1,186 @@
+pub mod constant_product;
+pub mod elasticity;
+pub mod fixed;
+pub mod supply_demand;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+pub use constant_product::ConstantProductParams;
+pub use elasticity::ElasticityParams;
+pub use fixed::FixedParams;
+pub use supply_demand::SupplyDemandParams;
+
+/// Buy price as a multiple of the sell price when a config doesn't set one.
+pub const DEFAULT_BUY_SPREAD: f64 = 1.6;
+
+/// Scope of the model that applies to every item without its own.
+pub const DEFAULT_SCOPE: &str = "*";
+
+/// Which way a player trade went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    /// The player sold items to the market.
+    Sell,
+    /// The player bought items from the market.
+    Buy,
+}
+
+/// Everything a model may look at when repricing an item.
+#[derive(Debug, Clone)]
+pub struct MarketState {
+    pub base_price: i64,
+    pub multiplier: f64,
+    pub min_multiplier: f64,
+    pub max_multiplier: f64,
+    /// Units sold to the market in the last hour, including the current trade.
+    pub recent_sold: i64,
+    /// Units bought from the market in the last hour, including the current trade.
+    pub recent_bought: i64,
+}
+
+impl MarketState {
+    pub fn clamp(&self, multiplier: f64) -> f64 {
+        multiplier.max(self.min_multiplier).min(self.max_multiplier)
+    }
+}
+
+#[cfg(test)]
+impl MarketState {
+    /// A quiet item at `multiplier` with the default 0.1 to 4.0 bounds.
+    pub(crate) fn at(multiplier: f64) -> Self {
+        Self {
+            base_price: 100,
+            multiplier,
+            min_multiplier: 0.1,
+            max_multiplier: 4.0,
+            recent_sold: 0,
+            recent_bought: 0,
+        }
+    }
+}
+
+/// A market pricing rule. Implementations are pure so the same code can run
+/// against the database or an in-memory simulation.
+pub trait PricingModel {
+    /// Multiplier after a player trades `quantity` units. Clamping to the
+    /// item's bounds is done by the caller.
+    fn after_trade(&self, state: &MarketState, side: Side, quantity: i32) -> f64;
+
+    /// Multiplier after one regeneration tick with the given pull toward 1.0.
+    fn regenerate(&self, state: &MarketState, decay: f64) -> f64 {
+        state.multiplier + (1.0 - state.multiplier) * decay
+    }
+
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// The model parameters stored per item (or globally) in `tb_pricing_models`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum ModelParams {
+    SupplyDemand(SupplyDemandParams),
+    ConstantProduct(ConstantProductParams),
+    Fixed(FixedParams),
+    Elasticity(ElasticityParams),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingConfig {
+    #[serde(flatten)]
+    pub params: ModelParams,
+    #[serde(default = "default_buy_spread")]
+    pub buy_spread: f64,
+}
+
+fn default_buy_spread() -> f64 {
+    DEFAULT_BUY_SPREAD
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            params: ModelParams::SupplyDemand(SupplyDemandParams::default()),
+            buy_spread: DEFAULT_BUY_SPREAD,
+        }
+    }
+}
+
+impl PricingConfig {
+    pub fn model(&self) -> &dyn PricingModel {
+        match &self.params {
+            ModelParams::SupplyDemand(params) => params,
+            ModelParams::ConstantProduct(params) => params,
+            ModelParams::Fixed(params) => params,
+            ModelParams::Elasticity(params) => params,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.buy_spread.is_finite() && self.buy_spread >= 1.0) {
+            return Err("buy_spread must be at least 1.0".to_string());
+        }
+        self.model().validate()
+    }
+
+    /// Clamped multiplier after a trade.
+    pub fn after_trade(&self, state: &MarketState, side: Side, quantity: i32) -> f64 {
+        state.clamp(self.model().after_trade(state, side, quantity))
+    }
+
+    /// Clamped multiplier after a regeneration tick.
+    pub fn regenerate(&self, state: &MarketState, decay: f64) -> f64 {
+        state.clamp(self.model().regenerate(state, decay))
+    }
+
+    /// Sell and buy price at `multiplier`.
+    pub fn prices(&self, base_price: i64, multiplier: f64) -> (i64, i64) {
+        let base_price = base_price as f64;
+        (
+            (base_price * multiplier) as i64,
+            (base_price * multiplier * self.buy_spread) as i64,
+        )
+    }
+}
+
+/// Every configured model, resolved per item with the global one as fallback.
+#[derive(Debug, Clone, Default)]
+pub struct PricingTable {
+    default: PricingConfig,
+    items: HashMap<String, PricingConfig>,
+}
+
+impl PricingTable {
+    /// Sets the model for an item key, or the global one for `DEFAULT_SCOPE`.
+    pub fn insert(&mut self, scope: String, config: PricingConfig) {
+        if scope == DEFAULT_SCOPE {
+            self.default = config;
+        } else {
+            self.items.insert(scope, config);
+        }
+    }
+
+    pub fn for_item(&self, item_key: &str) -> &PricingConfig {
+        self.items.get(item_key).unwrap_or(&self.default)
+    }
+}
+
+/// Slows regeneration for items that are actively traded: at
+/// `volume_reference` units since the last tick the pull is halved. A
+/// reference of 0 turns the damping off.
+pub fn volume_damped_decay(decay: f64, recent_volume: i64, volume_reference: f64) -> f64 {
+    if volume_reference <= 0.0 {
+        return decay;
+    }
+    decay / (1.0 + recent_volume as f64 / volume_reference)
+}
+
+pub(crate) fn require_positive(name: &str, value: f64) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("{} must be a positive number", name))
+    }
+}
@@ -0,0 +1,146 @@
+// pricing/supply_demand.rs
+use serde::{Deserialize, Serialize};
+
+use super::{require_positive, MarketState, PricingModel, Side};
+
+/// The original market formula: each stack traded nudges the multiplier,
+/// harder when the last hour was one-sided, with a slight pull back to 1.0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SupplyDemandParams {
+    /// Units that count as one full step (a stack).
+    pub volume_unit: f64,
+    /// Multiplier change per full step before the supply/demand weighting.
+    pub step: f64,
+    /// Ratio assumed when there were sales but no buys in the window.
+    pub ratio_fallback: f64,
+    /// Fraction of the distance to 1.0 recovered on every trade.
+    pub baseline_pull: f64,
+}
+
+impl Default for SupplyDemandParams {
+    fn default() -> Self {
+        Self {
+            volume_unit: 64.0,
+            step: 0.02,
+            ratio_fallback: 2.0,
+            baseline_pull: 0.001,
+        }
+    }
+}
+
+impl PricingModel for SupplyDemandParams {
+    fn after_trade(&self, state: &MarketState, side: Side, quantity: i32) -> f64 {
+        let sales_volume = state.recent_sold as f64;
+        let buy_volume = state.recent_bought as f64;
+
+        let supply_demand_ratio = if buy_volume > 0.0 {
+            sales_volume / buy_volume
+        } else if sales_volume > 0.0 {
+            self.ratio_fallback
+        } else {
+            1.0
+        };
+
+        let volume_factor = (quantity as f64) / self.volume_unit;
+        let price_change = match side {
+            Side::Sell => -self.step * volume_factor * (1.0 + supply_demand_ratio * 0.5),
+            Side::Buy => self.step * volume_factor * (1.0 + (1.0 / supply_demand_ratio.max(0.1)) * 0.5),
+        };
+
+        let mut multiplier = state.multiplier + price_change;
+        multiplier += (1.0 - multiplier) * self.baseline_pull;
+        multiplier
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        require_positive("volume_unit", self.volume_unit)?;
+        require_positive("step", self.step)?;
+        require_positive("ratio_fallback", self.ratio_fallback)?;
+        if !(0.0..=1.0).contains(&self.baseline_pull) {
+            return Err("baseline_pull must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::PricingConfig;
+
+    /// `update_market_price` before pricing models existed, kept verbatim.
+    #[allow(clippy::manual_clamp)]
+    fn baseline(multiplier: f64, sold: i64, bought: i64, transaction_type: &str, quantity: i32) -> f64 {
+        let sales_volume = sold as f64;
+        let buy_volume = bought as f64;
+        let mut current_multiplier = multiplier;
+
+        let supply_demand_ratio = if buy_volume > 0.0 {
+            sales_volume / buy_volume
+        } else if sales_volume > 0.0 {
+            2.0
+        } else {
+            1.0
+        };
+
+        let price_change = match transaction_type {
+            "SELL" => {
+                let volume_factor = (quantity as f64) / 64.0;
+                -0.02 * volume_factor * (1.0 + supply_demand_ratio * 0.5)
+            }
+            "BUY" => {
+                let volume_factor = (quantity as f64) / 64.0;
+                0.02 * volume_factor * (1.0 + (1.0 / supply_demand_ratio.max(0.1)) * 0.5)
+            }
+            _ => 0.0,
+        };
+
+        current_multiplier += price_change;
+        let baseline_pull = (1.0 - current_multiplier) * 0.001;
+        current_multiplier += baseline_pull;
+        current_multiplier.max(0.1).min(4.0)
+    }
+
+    #[test]
+    fn default_model_matches_the_baseline_formula() {
+        let config = PricingConfig::default();
+        let volumes = [(0, 0), (128, 0), (0, 64), (300, 120), (5, 500), (1, 100_000)];
+
+        for multiplier in [0.1, 0.5, 1.0, 2.3, 4.0] {
+            for (sold, bought) in volumes {
+                for quantity in [1, 64, 1_000, 100_000] {
+                    for (side, transaction_type) in [(Side::Sell, "SELL"), (Side::Buy, "BUY")] {
+                        let state = MarketState { recent_sold: sold, recent_bought: bought, ..MarketState::at(multiplier) };
+                        let expected = baseline(multiplier, sold, bought, transaction_type, quantity);
+
+                        let actual = config.after_trade(&state, side, quantity);
+                        assert_eq!(actual, expected, "{transaction_type} {quantity} at {multiplier} ({sold}/{bought})");
+                        assert_eq!(
+                            config.prices(1_234, actual),
+                            ((1_234.0 * expected) as i64, (1_234.0 * expected * 1.6) as i64)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn large_trades_stop_at_the_bounds() {
+        let config = PricingConfig::default();
+        let state = MarketState::at(1.0);
+
+        assert_eq!(config.after_trade(&state, Side::Sell, 100_000), 0.1);
+        assert_eq!(config.after_trade(&state, Side::Buy, 100_000), 4.0);
+    }
+
+    #[test]
+    fn validate_rejects_bad_params() {
+        assert!(SupplyDemandParams::default().validate().is_ok());
+        assert!(SupplyDemandParams { volume_unit: 0.0, ..Default::default() }.validate().is_err());
+        assert!(SupplyDemandParams { step: -0.02, ..Default::default() }.validate().is_err());
+        assert!(SupplyDemandParams { ratio_fallback: f64::NAN, ..Default::default() }.validate().is_err());
+        assert!(SupplyDemandParams { baseline_pull: 1.5, ..Default::default() }.validate().is_err());
+    }
+}
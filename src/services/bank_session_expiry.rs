@@ -0,0 +1,37 @@
+// services/bank_session_expiry.rs
+use sqlx::MySqlPool;
+use tokio::time::{interval, Duration};
+use tracing;
+
+use crate::{api::bank::expire_bank_sessions, services::supervisor::Shutdown};
+
+/// Closes bank sessions the game server never closed itself (crashes,
+/// disconnects) so `is_bank_open` doesn't stay stuck at 1.
+pub struct BankSessionExpiryService {
+    pool: MySqlPool,
+}
+
+impl BankSessionExpiryService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn start(&self, mut shutdown: Shutdown) {
+        let mut interval_timer = interval(Duration::from_secs(15));
+
+        tracing::info!("🏦 Bank session expiry service started (every 15 seconds)");
+
+        loop {
+            tokio::select! {
+                _ = interval_timer.tick() => {}
+                _ = shutdown.wait() => break,
+            }
+
+            match expire_bank_sessions(&self.pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("🏦 Expired {} bank sessions", count),
+                Err(e) => tracing::error!("Bank session expiry failed: {:?}", e),
+            }
+        }
+    }
+}
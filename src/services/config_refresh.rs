@@ -0,0 +1,39 @@
+// services/config_refresh.rs
+use sqlx::MySqlPool;
+use tokio::time::{sleep, Duration};
+use tracing;
+
+use crate::{api::config::ConfigHandle, services::supervisor::Shutdown};
+
+/// Periodically reloads `tb_config` into the shared handle so edits made
+/// directly in the database still reach the running server.
+pub struct ConfigRefreshService {
+    pool: MySqlPool,
+    config: ConfigHandle,
+}
+
+impl ConfigRefreshService {
+    pub fn new(pool: MySqlPool, config: ConfigHandle) -> Self {
+        Self { pool, config }
+    }
+
+    pub async fn start(&self, mut shutdown: Shutdown) {
+        tracing::info!(
+            "🔄 Config refresh service started (every {}s)",
+            self.config.current().config_refresh_interval_secs
+        );
+
+        loop {
+            // Re-read each time so the interval itself can be changed live
+            let secs = self.config.current().config_refresh_interval_secs.max(1);
+            tokio::select! {
+                _ = sleep(Duration::from_secs(secs)) => {}
+                _ = shutdown.wait() => break,
+            }
+
+            if let Err(e) = self.config.reload(&self.pool).await {
+                tracing::error!("Config refresh failed: {:?}", e);
+            }
+        }
+    }
+}
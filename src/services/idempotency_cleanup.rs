@@ -0,0 +1,37 @@
+// services/idempotency_cleanup.rs
+use sqlx::MySqlPool;
+use tokio::time::{interval, Duration};
+use tracing;
+
+use crate::{api::idempotency::purge_expired_idempotency_keys, services::supervisor::Shutdown};
+
+/// Deletes idempotency keys past `idempotency_key_ttl_secs`. Expired keys
+/// are already ignored on lookup, this only keeps the table small.
+pub struct IdempotencyCleanupService {
+    pool: MySqlPool,
+}
+
+impl IdempotencyCleanupService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn start(&self, mut shutdown: Shutdown) {
+        let mut interval_timer = interval(Duration::from_secs(600));
+
+        tracing::info!("🔁 Idempotency key cleanup service started (every 10 minutes)");
+
+        loop {
+            tokio::select! {
+                _ = interval_timer.tick() => {}
+                _ = shutdown.wait() => break,
+            }
+
+            match purge_expired_idempotency_keys(&self.pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("🔁 Purged {} expired idempotency keys", count),
+                Err(e) => tracing::error!("Idempotency key cleanup failed: {:?}", e),
+            }
+        }
+    }
+}
@@ -1 +1,6 @@
-pub mod price_regeneration;
\ No newline at end of file
+pub mod bank_session_expiry;
+pub mod config_refresh;
+pub mod idempotency_cleanup;
+pub mod price_regeneration;
+pub mod signature_cleanup;
+pub mod supervisor;
\ No newline at end of file
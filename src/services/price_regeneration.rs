@@ -1,44 +1,67 @@
 // services/price_regeneration.rs
+use chrono::Utc;
 use sqlx::MySqlPool;
-use tokio::time::{interval, Duration};
+use tokio::time::{sleep, Duration};
 use tracing;
 
+use crate::{
+    api::{
+        config::ConfigHandle,
+        regeneration::{next_run_at, regenerate_prices, RunTrigger},
+    },
+    services::supervisor::Shutdown,
+};
+
+/// Longest the service sleeps before re-checking the schedule, so interval
+/// changes and resumes take effect without waiting out a full interval.
+const MAX_SLEEP_SECS: u64 = 60;
+
 pub struct PriceRegenerationService {
     pool: MySqlPool,
+    config: ConfigHandle,
 }
 
 impl PriceRegenerationService {
-    pub fn new(pool: MySqlPool) -> Self {
-        Self { pool }
+    pub fn new(pool: MySqlPool, config: ConfigHandle) -> Self {
+        Self { pool, config }
     }
 
-    pub async fn start(&self) {
-        let mut interval_timer = interval(Duration::from_secs(3 * 60 * 60)); // 3 hours
-        
-        tracing::info!("🔄 Price regeneration service started (every 3 hours)");
-        
+    pub async fn start(&self, mut shutdown: Shutdown) {
+        tracing::info!(
+            "🔄 Price regeneration service started (every {}s)",
+            self.config.current().regeneration_interval_secs
+        );
+
         loop {
-            interval_timer.tick().await;
-            
-            if let Err(e) = self.regenerate_prices().await {
-                tracing::error!("Price regeneration failed: {:?}", e);
-            } else {
-                tracing::info!("✅ Price regeneration completed");
+            // Re-read each time so the schedule and pause flag can be changed live
+            let config = self.config.current();
+
+            let wait_secs = match next_run_at(&self.pool, &config).await {
+                Ok(Some(due)) => (due - Utc::now()).num_seconds().max(0) as u64,
+                Ok(None) => 0,
+                Err(e) => {
+                    tracing::error!("Failed to read the regeneration schedule: {:?}", e);
+                    MAX_SLEEP_SECS
+                }
+            };
+            if wait_secs > 0 || config.regeneration_paused {
+                tokio::select! {
+                    _ = sleep(Duration::from_secs(wait_secs.clamp(1, MAX_SLEEP_SECS))) => continue,
+                    _ = shutdown.wait() => break,
+                }
             }
-        }
-    }
 
-    async fn regenerate_prices(&self) -> Result<(), sqlx::Error> {
-        let result = sqlx::query!(
-            "UPDATE tb_market_items SET 
-             price_multiplier = LEAST(3.0, price_multiplier + (1.0 - price_multiplier) * 0.1),
-             current_sell_price = ROUND(base_price * LEAST(3.0, price_multiplier + (1.0 - price_multiplier) * 0.1)),
-             current_buy_price = ROUND(base_price * LEAST(3.0, price_multiplier + (1.0 - price_multiplier) * 0.1) * 1.6)"
-        )
-        .execute(&self.pool)
-        .await?;
-
-        tracing::info!("Regenerated prices for {} items", result.rows_affected());
-        Ok(())
+            match regenerate_prices(&self.pool, &config, RunTrigger::Scheduled, None).await {
+                Ok(run) => tracing::info!("✅ Price regeneration completed ({} items)", run.items_updated),
+                Err(e) => {
+                    // The failure is logged as a run, retry after a short pause
+                    tracing::error!("Price regeneration failed: {:?}", e);
+                    tokio::select! {
+                        _ = sleep(Duration::from_secs(MAX_SLEEP_SECS)) => {}
+                        _ = shutdown.wait() => break,
+                    }
+                }
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,37 @@
+// services/signature_cleanup.rs
+use sqlx::MySqlPool;
+use tokio::time::{interval, Duration};
+use tracing;
+
+use crate::{api::auth::purge_expired_signatures, services::supervisor::Shutdown};
+
+/// Deletes used HMAC signatures once their timestamps are outside the allowed
+/// clock skew, when they could no longer be replayed anyway.
+pub struct SignatureCleanupService {
+    pool: MySqlPool,
+}
+
+impl SignatureCleanupService {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn start(&self, mut shutdown: Shutdown) {
+        let mut interval_timer = interval(Duration::from_secs(600));
+
+        tracing::info!("🔏 Signature cleanup service started (every 10 minutes)");
+
+        loop {
+            tokio::select! {
+                _ = interval_timer.tick() => {}
+                _ = shutdown.wait() => break,
+            }
+
+            match purge_expired_signatures(&self.pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("🔏 Purged {} expired request signatures", count),
+                Err(e) => tracing::error!("Signature cleanup failed: {:?}", e),
+            }
+        }
+    }
+}
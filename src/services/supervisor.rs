@@ -0,0 +1,161 @@
+// services/supervisor.rs
+use std::future::Future;
+
+use tokio::{
+    sync::watch,
+    task::{AbortHandle, JoinSet},
+    time::{sleep, timeout, Duration, Instant},
+};
+
+/// First delay before restarting a service that panicked, doubled on each
+/// consecutive crash up to `MAX_BACKOFF`.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A service that stayed up this long is considered healthy again.
+const HEALTHY_AFTER: Duration = Duration::from_secs(300);
+
+/// Handed to every background service so it can stop between runs.
+#[derive(Clone)]
+pub struct Shutdown {
+    receiver: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    pub fn is_triggered(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Resolves once shutdown starts. Use it in a `select!` against the service's timer.
+    pub async fn wait(&mut self) {
+        // An error means the supervisor is gone, which is a shutdown too
+        let _ = self.receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Aborts the service task if its supervisor is aborted first.
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Runs background services, restarts them with backoff if they panic and
+/// stops them when the server shuts down.
+pub struct Supervisor {
+    sender: watch::Sender<bool>,
+    tasks: JoinSet<()>,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender,
+            tasks: JoinSet::new(),
+        }
+    }
+
+    fn shutdown(&self) -> Shutdown {
+        Shutdown {
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    /// Starts a service built by `run`, which is called again after every panic.
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, run: F)
+    where
+        F: Fn(Shutdown) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut shutdown = self.shutdown();
+
+        self.tasks.spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+
+            loop {
+                let started = Instant::now();
+                // A separate task so a panic is caught here instead of killing the supervisor
+                let handle = tokio::spawn(run(shutdown.clone()));
+                let _guard = AbortOnDrop(handle.abort_handle());
+                let result = handle.await;
+
+                if shutdown.is_triggered() {
+                    break;
+                }
+                match result {
+                    Ok(()) => tracing::warn!("⚠️ {} stopped unexpectedly, restarting", name),
+                    Err(e) if e.is_panic() => tracing::error!("💥 {} panicked, restarting in {:?}", name, backoff),
+                    Err(e) => tracing::error!("💥 {} was cancelled ({}), restarting in {:?}", name, e, backoff),
+                }
+
+                if started.elapsed() >= HEALTHY_AFTER {
+                    backoff = INITIAL_BACKOFF;
+                }
+                tokio::select! {
+                    _ = sleep(backoff) => {}
+                    _ = shutdown.wait() => break,
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+
+            tracing::info!("🛑 {} stopped", name);
+        });
+    }
+
+    /// Signals every service to stop and waits up to `grace` for them to
+    /// finish their current run before aborting the rest.
+    pub async fn stop(mut self, grace: Duration) {
+        let _ = self.sender.send(true);
+
+        let drained = timeout(grace, async {
+            while self.tasks.join_next().await.is_some() {}
+        })
+        .await;
+
+        if drained.is_err() {
+            tracing::warn!("⚠️ Background services did not stop within {:?}, aborting them", grace);
+            self.tasks.shutdown().await;
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves on Ctrl+C, or SIGTERM on Unix.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    tracing::info!("🛑 Shutdown signal received, draining requests");
+}